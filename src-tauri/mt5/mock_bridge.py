@@ -0,0 +1,122 @@
+"""MT5 のない環境でティック配信を確かめるための代替サーバー
+
+mt5_server と同じエンドポイントを持ち、/stream_ticks ではランダムウォークの
+ティックを送り続ける。設定の host / port をこのサーバーに向けて使う。
+
+    python mock_bridge.py --port 5001 --interval 0.2
+"""
+from flask import Flask, Response, request, jsonify, stream_with_context
+import argparse
+import json
+import os
+import random
+import threading
+import time
+
+app = Flask(__name__)
+
+DEFAULT_SYMBOL = "USDJPY"
+
+# 通貨ペアごとの初期レート
+START_PRICES = {
+    "USDJPY": 150.000,
+    "EURJPY": 162.000,
+    "EURUSD": 1.08000,
+}
+
+HEARTBEAT_SECS = 15
+
+# --interval で上書きする
+tick_interval = 0.5
+
+
+@app.route("/health")
+def health():
+    return jsonify({"status": "ok", "terminal_connected": False, "mock": True})
+
+
+@app.route("/shutdown", methods=["POST"])
+def shutdown():
+    threading.Timer(0.5, lambda: os._exit(0)).start()
+    return jsonify({"status": "shutting_down"})
+
+
+@app.route("/get_symbols")
+def get_symbols():
+    result = []
+    for name in START_PRICES:
+        jpy = name.endswith("JPY")
+        result.append({
+            "name": name,
+            "description": f"{name[:3]} vs {name[3:]}",
+            "path": f"Forex\\{name}",
+            "currency_base": name[:3],
+            "currency_profit": name[3:],
+            "digits": 3 if jpy else 5,
+            "point": 0.001 if jpy else 0.00001,
+            "contract_size": 100000.0,
+            "volume_min": 0.01,
+            "volume_max": 100.0,
+            "volume_step": 0.01,
+            "swap_long": 0.0,
+            "swap_short": 0.0,
+            "swap_mode": 1,
+            "trade_mode": 4,
+            "sessions": [
+                {"weekday": d, "open": "00:00", "close": "24:00"} for d in range(5)
+            ],
+        })
+    return jsonify(result)
+
+
+@app.route("/get_ohlc")
+@app.route("/get_ticks")
+@app.route("/get_deals")
+@app.route("/get_positions")
+def empty():
+    return jsonify([])
+
+
+@app.route("/stream_ticks")
+def stream_ticks():
+    symbols = request.args.get("symbols", DEFAULT_SYMBOL).split(",")
+
+    def generate():
+        prices = {s: START_PRICES.get(s, 100.0) for s in symbols}
+        last_sent = time.time()
+        while True:
+            for symbol in symbols:
+                # JPY 建ては 0.001、それ以外は 0.00001 刻み
+                point = 0.001 if symbol.endswith("JPY") else 0.00001
+                prices[symbol] += random.choice([-3, -2, -1, 0, 1, 2, 3]) * point
+
+                now_msc = int(time.time() * 1000)
+                bid = round(prices[symbol], 5)
+                data = {
+                    "time": now_msc // 1000,
+                    "time_msc": now_msc,
+                    "bid": bid,
+                    "ask": round(bid + point * 2, 5),
+                    "pair": symbol,
+                }
+                yield f"data: {json.dumps(data)}\n\n"
+                last_sent = time.time()
+
+            if time.time() - last_sent >= HEARTBEAT_SECS:
+                yield ": ping\n\n"
+                last_sent = time.time()
+
+            time.sleep(tick_interval)
+
+    return Response(stream_with_context(generate()), mimetype="text/event-stream")
+
+
+if __name__ == "__main__":
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--host", default="127.0.0.1")
+    parser.add_argument("--port", type=int, default=5001)
+    parser.add_argument("--interval", type=float, default=0.5)
+    args, _ = parser.parse_known_args()
+
+    tick_interval = args.interval
+    app.run(host=args.host, port=args.port, threaded=True)
@@ -12,10 +12,13 @@ if getattr(sys, 'frozen', False):
     if numpy_path not in sys.path:
         sys.path.append(numpy_path)
 
-from flask import Flask, request, jsonify
+from flask import Flask, Response, request, jsonify, stream_with_context
 import MetaTrader5 as mt5
 from datetime import datetime, timedelta
+import argparse
+import json
 import pytz
+import threading
 import time
 
 app = Flask(__name__)
@@ -24,6 +27,18 @@ if not mt5.initialize():
     print("MT5 initialize failed")
     exit()
 
+DEFAULT_SYMBOL = "USDJPY"
+
+TIMEFRAMES = {
+    "M1": mt5.TIMEFRAME_M1,
+    "M5": mt5.TIMEFRAME_M5,
+    "M15": mt5.TIMEFRAME_M15,
+    "M30": mt5.TIMEFRAME_M30,
+    "H1": mt5.TIMEFRAME_H1,
+    "H4": mt5.TIMEFRAME_H4,
+    "D1": mt5.TIMEFRAME_D1,
+}
+
 def is_helsinki_dst(unix_timestamp: int) -> bool:
     utc_dt = datetime.fromtimestamp(unix_timestamp, tz=pytz.utc)
 
@@ -68,11 +83,33 @@ def get_ohlc_since(symbol: str, timeframe, since_time: int, batch_size=1000):
     return all_rates
 
 
+@app.route("/health")
+def health():
+    return jsonify({
+        "status": "ok",
+        "terminal_connected": mt5.terminal_info() is not None,
+    })
+
+
+@app.route("/shutdown", methods=["POST"])
+def shutdown():
+    # レスポンスを返してからプロセスを終了する
+    mt5.shutdown()
+    threading.Timer(0.5, lambda: os._exit(0)).start()
+    return jsonify({"status": "shutting_down"})
+
+
 @app.route("/get_ohlc")
 def get_ohlc():
+    symbol = request.args.get("symbol", DEFAULT_SYMBOL)
+    timeframe = request.args.get("timeframe", "M1")
     since = int(request.args.get("since", 0))
     batch_size = int(request.args.get("batch_size", 1000))
-    rates = get_ohlc_since("USDJPY", mt5.TIMEFRAME_M1, since, batch_size)
+
+    if timeframe not in TIMEFRAMES:
+        return jsonify({"error": f"unknown timeframe: {timeframe}"}), 400
+
+    rates = get_ohlc_since(symbol, TIMEFRAMES[timeframe], since, batch_size)
 
     result = []
     if rates is not None:
@@ -84,7 +121,7 @@ def get_ohlc():
                 "high": float(r['high']),
                 "low": float(r['low']),
                 "close": float(r['close']),
-                "pair": "USDJPY",
+                "pair": symbol,
                 "tickvol": int(r['tick_volume']),
                 "vol": 0,
                 "spread": 0,
@@ -94,6 +131,7 @@ def get_ohlc():
 
 @app.route("/get_ticks")
 def get_ticks():
+    symbol = request.args.get("symbol", DEFAULT_SYMBOL)
     from_time = int(request.args.get("since", 0)) - 3600 * 24
     to_time = from_time + 3600 * 10
 
@@ -101,7 +139,7 @@ def get_ticks():
 
     all_ticks = []
     while True:
-        ticks = mt5.copy_ticks_range("USDJPY", from_time, to_time, mt5.COPY_TICKS_ALL)
+        ticks = mt5.copy_ticks_range(symbol, from_time, to_time, mt5.COPY_TICKS_ALL)
         print(len(ticks))
 
         # レスポンスの時刻はヨーロッパ時刻で渡される
@@ -114,7 +152,7 @@ def get_ticks():
                 "time_msc": int(utc_time_msc),
                 "bid": float(t["bid"]),
                 "ask": float(t["ask"]),
-                "pair": "USDJPY"
+                "pair": symbol
             })
         
         if current_unix_time + 3600 * 24 < to_time :
@@ -129,5 +167,186 @@ def get_ticks():
 
     return jsonify(sorted_ticks)
 
+def observed_sessions(symbol: str):
+    """直近 2 週間の 1 時間足から曜日ごとの取引時間帯（UTC）を推定する
+
+    MetaTrader5 パッケージには SymbolInfoSessionTrade がないため、
+    実際に足が立っている時間から求める
+    """
+    rates = mt5.copy_rates_from_pos(symbol, mt5.TIMEFRAME_H1, 0, 24 * 14)
+    if rates is None or len(rates) == 0:
+        return []
+
+    hours = {}
+    for r in rates:
+        utc = datetime.fromtimestamp(broker_to_utc_unixtime(int(r["time"])), tz=pytz.utc)
+        hours.setdefault(utc.weekday(), set()).add(utc.hour)
+
+    sessions = []
+    for weekday in sorted(hours):
+        # 連続する時間をまとめて 1 つの時間帯にする
+        start = prev = None
+        for h in sorted(hours[weekday]) + [None]:
+            if start is None:
+                start = prev = h
+            elif h is not None and h == prev + 1:
+                prev = h
+            else:
+                sessions.append({
+                    "weekday": weekday,
+                    "open": f"{start:02d}:00",
+                    "close": f"{prev + 1:02d}:00",
+                })
+                start = prev = h
+
+    return sessions
+
+
+@app.route("/get_symbols")
+def get_symbols():
+    symbols = mt5.symbols_get()
+    if symbols is None:
+        return jsonify({"error": str(mt5.last_error())}), 500
+
+    result = []
+    for s in symbols:
+        result.append({
+            "name": s.name,
+            "description": s.description,
+            "path": s.path,
+            "currency_base": s.currency_base,
+            "currency_profit": s.currency_profit,
+            "digits": int(s.digits),
+            "point": float(s.point),
+            "contract_size": float(s.trade_contract_size),
+            "volume_min": float(s.volume_min),
+            "volume_max": float(s.volume_max),
+            "volume_step": float(s.volume_step),
+            "swap_long": float(s.swap_long),
+            "swap_short": float(s.swap_short),
+            "swap_mode": int(s.swap_mode),
+            "trade_mode": int(s.trade_mode),
+            # 全銘柄分の足を読むと重いので気配値表示にある銘柄だけ
+            "sessions": observed_sessions(s.name) if s.visible else [],
+        })
+
+    return jsonify(result)
+
+
+# Server-Sent Events のハートビート間隔（秒）
+HEARTBEAT_SECS = 15
+
+
+@app.route("/stream_ticks")
+def stream_ticks():
+    symbols = request.args.get("symbols", DEFAULT_SYMBOL).split(",")
+
+    def generate():
+        # 接続時点の最新ティックから送り始める
+        last_msc = {}
+        for symbol in symbols:
+            mt5.symbol_select(symbol, True)
+            tick = mt5.symbol_info_tick(symbol)
+            last_msc[symbol] = int(tick.time_msc) if tick is not None else 0
+
+        last_sent = time.time()
+        while True:
+            for symbol in symbols:
+                since = last_msc[symbol] // 1000
+                ticks = mt5.copy_ticks_from(symbol, since, 1000, mt5.COPY_TICKS_ALL)
+                if ticks is None:
+                    continue
+
+                for t in ticks:
+                    broker_msc = int(t["time_msc"])
+                    if broker_msc <= last_msc[symbol]:
+                        continue
+                    last_msc[symbol] = broker_msc
+
+                    utc_time = broker_to_utc_unixtime(int(t["time"]))
+                    data = {
+                        "time": utc_time,
+                        "time_msc": utc_time * 1000 + broker_msc % 1000,
+                        "bid": float(t["bid"]),
+                        "ask": float(t["ask"]),
+                        "pair": symbol,
+                    }
+                    yield f"data: {json.dumps(data)}\n\n"
+                    last_sent = time.time()
+
+            if time.time() - last_sent >= HEARTBEAT_SECS:
+                yield ": ping\n\n"
+                last_sent = time.time()
+
+            time.sleep(0.1)
+
+    return Response(stream_with_context(generate()), mimetype="text/event-stream")
+
+
+@app.route("/get_positions")
+def get_positions():
+    positions = mt5.positions_get()
+    if positions is None:
+        return jsonify({"error": str(mt5.last_error())}), 500
+
+    result = []
+    for p in positions:
+        result.append({
+            "ticket": int(p.ticket),
+            "symbol": p.symbol,
+            "type": int(p.type),
+            "volume": float(p.volume),
+            "price_open": float(p.price_open),
+            "time": broker_to_utc_unixtime(int(p.time)),
+            "swap": float(p.swap),
+        })
+
+    return jsonify(result)
+
+@app.route("/get_deals")
+def get_deals():
+    # 取り込み済みの最新約定から 1 日戻って取得し、重複はチケットで弾く
+    since = int(request.args.get("since", 0))
+    from_time = datetime.fromtimestamp(max(since - 3600 * 24, 0))
+    to_time = datetime.now() + timedelta(days=1)
+
+    deals = mt5.history_deals_get(from_time, to_time)
+    if deals is None:
+        return jsonify({"error": str(mt5.last_error())}), 500
+
+    result = []
+    for d in deals:
+        # 入出金などは対象外
+        if d.type not in (mt5.DEAL_TYPE_BUY, mt5.DEAL_TYPE_SELL):
+            continue
+
+        utc_time = broker_to_utc_unixtime(int(d.time))
+        result.append({
+            "ticket": int(d.ticket),
+            "position_id": int(d.position_id),
+            "order": int(d.order),
+            "symbol": d.symbol,
+            "type": int(d.type),
+            "entry": int(d.entry),
+            "volume": float(d.volume),
+            "price": float(d.price),
+            "profit": float(d.profit),
+            "swap": float(d.swap),
+            "commission": float(d.commission),
+            "time": utc_time,
+            "time_msc": utc_time * 1000 + (int(d.time_msc) % 1000),
+            "magic": int(d.magic),
+            "comment": d.comment,
+        })
+
+    result.sort(key=lambda x: (x["time_msc"], x["ticket"]))
+    return jsonify(result)
+
 if __name__ == "__main__":
-    app.run(host="127.0.0.1", port=5000)
+    parser = argparse.ArgumentParser()
+    parser.add_argument("--host", default="127.0.0.1")
+    parser.add_argument("--port", type=int, default=5000)
+    args, _ = parser.parse_known_args()
+
+    # ティック配信の接続中も他のリクエストに応答できるようにする
+    app.run(host=args.host, port=args.port, threaded=True)
@@ -1,34 +1,189 @@
-use crate::db::queries::{candles, ticks};
 use crate::db::DbState;
-use crate::mt5_client;
-use tauri::State;
+use crate::market_data::MarketDataState;
+use crate::models::db::symbol::SymbolSpec;
+use crate::models::service::bridge_status::BridgeStatus;
+use crate::models::service::deal_import::DealImportSummary;
+use crate::models::service::position::PositionSnapshot;
+use crate::models::service::scheduler_status::SchedulerStatus;
+use crate::models::service::sync::{LastSync, SyncKind, SyncProgress, SyncSummary};
+use crate::models::service::tick_stream::TickStreamStatus;
+use crate::python_server::{BridgeSupervisor, BRIDGE_STATUS_EVENT};
+use crate::service::positions::PositionMonitorState;
+use crate::service::scheduler::SchedulerState;
+use crate::service::sync::{SyncState, SYNC_PROGRESS_EVENT};
+use crate::service::tick_stream::TickStreamState;
+use tauri::{AppHandle, Emitter, State};
+
+async fn run_sync(
+    app: &AppHandle,
+    db: &DbState,
+    market: &MarketDataState,
+    sync: &SyncState,
+    kinds: &[SyncKind],
+) -> Result<SyncSummary, String> {
+    let guard = sync.begin()?;
+    crate::service::sync::run_sync(db, market, kinds, &guard.cancel, |p: &SyncProgress| {
+        let _ = app.emit(SYNC_PROGRESS_EVENT, p.clone());
+    })
+    .await
+}
 
 #[tauri::command]
-pub async fn fetch_and_update_ohlc(state: State<'_, DbState>) -> Result<String, String> {
-    let db = &*state;
-    let last_time = candles::get_latest_time(db).map_err(|e| e.to_string())?;
-    let candles = mt5_client::fetch_ohlc(last_time)
-        .await
-        .map_err(|e| e.to_string())?;
+pub async fn fetch_and_update_ohlc(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    market: State<'_, MarketDataState>,
+    sync: State<'_, SyncState>,
+) -> Result<String, String> {
+    let summary = run_sync(&app, &state, &market, &sync, &[SyncKind::Ohlc]).await?;
+    if !summary.errors.is_empty() {
+        return Err(summary.errors.join("\n"));
+    }
 
-    if !candles.is_empty() {
-        candles::insert_candles_bulk(db, &candles)?;
+    Ok(format!("Fetched {} candles", summary.candles))
+}
+
+#[tauri::command]
+pub async fn fetch_and_update_tick(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    market: State<'_, MarketDataState>,
+    sync: State<'_, SyncState>,
+) -> Result<String, String> {
+    let summary = run_sync(&app, &state, &market, &sync, &[SyncKind::Tick]).await?;
+    if !summary.errors.is_empty() {
+        return Err(summary.errors.join("\n"));
     }
 
-    Ok(format!("Fetched {} candles", candles.len()))
+    Ok(format!("Fetched {} ticks", summary.ticks))
 }
 
 #[tauri::command]
-pub async fn fetch_and_update_tick(state: State<'_, DbState>) -> Result<String, String> {
+pub async fn start_market_sync(
+    app: AppHandle,
+    state: State<'_, DbState>,
+    market: State<'_, MarketDataState>,
+    sync: State<'_, SyncState>,
+    kinds: Option<Vec<SyncKind>>,
+) -> Result<SyncSummary, String> {
+    let kinds = kinds.unwrap_or_else(|| vec![SyncKind::Ohlc, SyncKind::Tick]);
+    run_sync(&app, &state, &market, &sync, &kinds).await
+}
+
+#[tauri::command]
+pub fn cancel_market_sync(sync: State<SyncState>) -> Result<bool, String> {
+    sync.cancel()
+}
+
+#[tauri::command]
+pub fn get_last_syncs(
+    state: State<DbState>,
+    market: State<MarketDataState>,
+) -> Result<Vec<LastSync>, String> {
     let db = &*state;
-    let last_time = ticks::get_latest_time(db).map_err(|e| e.to_string())?;
-    let ticks = mt5_client::fetch_tick(last_time)
-        .await
-        .map_err(|e| e.to_string())?;
+    crate::service::sync::get_last_syncs(db, &market)
+}
 
-    if !ticks.is_empty() {
-        ticks::insert_ticks_bulk(db, &ticks)?;
+#[tauri::command]
+pub fn get_sync_scheduler_status(
+    scheduler: State<SchedulerState>,
+) -> Result<SchedulerStatus, String> {
+    scheduler.status()
+}
+
+#[tauri::command]
+pub fn pause_sync_scheduler(
+    scheduler: State<SchedulerState>,
+    sync: State<SyncState>,
+    seconds: Option<i64>,
+) -> Result<SchedulerStatus, String> {
+    // 定期同期の途中なら止める（手動同期はそのまま）
+    if scheduler.status()?.syncing {
+        sync.cancel()?;
     }
+    scheduler.pause(seconds)
+}
 
-    Ok(format!("Fetched {} ticks", ticks.len()))
+#[tauri::command]
+pub fn resume_sync_scheduler(scheduler: State<SchedulerState>) -> Result<SchedulerStatus, String> {
+    scheduler.resume()
+}
+
+#[tauri::command]
+pub fn get_bridge_status(supervisor: State<BridgeSupervisor>) -> Result<BridgeStatus, String> {
+    supervisor.status()
+}
+
+#[tauri::command]
+pub fn restart_bridge(
+    app: AppHandle,
+    supervisor: State<BridgeSupervisor>,
+    market: State<MarketDataState>,
+) -> Result<BridgeStatus, String> {
+    let status = supervisor.restart(&market.config()?)?;
+    let _ = app.emit(BRIDGE_STATUS_EVENT, status.clone());
+    Ok(status)
+}
+
+#[tauri::command]
+pub async fn fetch_and_update_deals(
+    state: State<'_, DbState>,
+    market: State<'_, MarketDataState>,
+) -> Result<DealImportSummary, String> {
+    let db = &*state;
+    crate::service::deals::import_deals(db, &market).await
+}
+
+#[tauri::command]
+pub async fn get_open_positions(
+    state: State<'_, DbState>,
+    market: State<'_, MarketDataState>,
+) -> Result<PositionSnapshot, String> {
+    let db = &*state;
+    crate::service::positions::get_snapshot(db, &market).await
+}
+
+#[tauri::command]
+pub fn start_position_monitor(
+    monitor: State<PositionMonitorState>,
+) -> Result<Option<PositionSnapshot>, String> {
+    monitor.set_enabled(true);
+    monitor.latest()
+}
+
+#[tauri::command]
+pub fn stop_position_monitor(monitor: State<PositionMonitorState>) {
+    monitor.set_enabled(false);
+}
+
+#[tauri::command]
+pub fn start_tick_stream(
+    app: AppHandle,
+    market: State<MarketDataState>,
+    symbols: Option<Vec<String>>,
+) -> Result<TickStreamStatus, String> {
+    let symbols = match symbols {
+        Some(s) if !s.is_empty() => s,
+        _ => market.config()?.symbols,
+    };
+    crate::service::tick_stream::start(&app, symbols)
+}
+
+#[tauri::command]
+pub fn stop_tick_stream(stream: State<TickStreamState>) -> Result<bool, String> {
+    stream.stop()
+}
+
+#[tauri::command]
+pub fn get_tick_stream_status(stream: State<TickStreamState>) -> Result<TickStreamStatus, String> {
+    stream.status()
+}
+
+#[tauri::command]
+pub async fn sync_symbol_catalog(
+    state: State<'_, DbState>,
+    market: State<'_, MarketDataState>,
+) -> Result<Vec<SymbolSpec>, String> {
+    let db = &*state;
+    crate::service::symbols::sync_catalog(db, &market).await
 }
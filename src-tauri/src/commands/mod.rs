@@ -1,5 +1,7 @@
 pub mod handlers;
 pub mod records_cmd;
+pub mod settings_cmd;
+pub mod stream_cmd;
 pub mod update_cmd;
 
 macro_rules! register_commands {
@@ -12,21 +14,80 @@ macro_rules! register_commands {
             crate::commands::records_cmd::get_all_records,
             crate::commands::records_cmd::get_all_trades,
             crate::commands::records_cmd::get_daily_records,
+            crate::commands::records_cmd::get_daily_summaries,
+            crate::commands::records_cmd::get_period_records,
             crate::commands::records_cmd::get_candles,
             crate::commands::records_cmd::add_label,
             crate::commands::records_cmd::get_all_labels,
             crate::commands::records_cmd::get_all_labels_with_trade,
+            crate::commands::records_cmd::get_label_summaries,
             crate::commands::records_cmd::add_trade_label,
             crate::commands::records_cmd::delete_trade_label,
             crate::commands::records_cmd::get_labels_for_trade,
             crate::commands::handlers::fetch_and_update_ohlc,
             crate::commands::handlers::fetch_and_update_tick,
+            crate::commands::handlers::fetch_and_update_deals,
+            crate::commands::handlers::start_market_sync,
+            crate::commands::handlers::cancel_market_sync,
+            crate::commands::handlers::get_last_syncs,
+            crate::commands::handlers::get_sync_scheduler_status,
+            crate::commands::handlers::pause_sync_scheduler,
+            crate::commands::handlers::resume_sync_scheduler,
+            crate::commands::handlers::get_bridge_status,
+            crate::commands::handlers::restart_bridge,
+            crate::commands::handlers::get_open_positions,
+            crate::commands::handlers::start_position_monitor,
+            crate::commands::handlers::stop_position_monitor,
+            crate::commands::handlers::start_tick_stream,
+            crate::commands::handlers::stop_tick_stream,
+            crate::commands::handlers::get_tick_stream_status,
+            crate::commands::handlers::sync_symbol_catalog,
             crate::commands::records_cmd::update_memo,
+            crate::commands::records_cmd::create_trade,
+            crate::commands::records_cmd::update_trade,
+            crate::commands::records_cmd::delete_trade,
+            crate::commands::records_cmd::get_trash,
+            crate::commands::records_cmd::restore_trade,
+            crate::commands::records_cmd::purge_trades,
             crate::commands::records_cmd::get_filtered_trades_summary,
+            crate::commands::records_cmd::get_filtered_summary,
+            crate::commands::records_cmd::get_trades_page,
+            crate::commands::records_cmd::compute_trade_excursions,
+            crate::commands::records_cmd::get_trade_excursion,
+            crate::commands::records_cmd::get_trade_context,
+            crate::commands::records_cmd::get_equity_curve,
+            crate::commands::records_cmd::get_trade_heatmap,
+            crate::commands::records_cmd::get_post_exit_analysis,
+            crate::commands::records_cmd::get_execution_quality,
             crate::commands::records_cmd::merge_trades,
+            crate::commands::records_cmd::unmerge_trade,
+            crate::commands::records_cmd::split_trade,
+            crate::commands::records_cmd::create_campaign,
+            crate::commands::records_cmd::update_campaign,
+            crate::commands::records_cmd::delete_campaign,
+            crate::commands::records_cmd::add_trades_to_campaign,
+            crate::commands::records_cmd::remove_trades_from_campaign,
+            crate::commands::records_cmd::get_campaign,
+            crate::commands::records_cmd::get_campaigns,
+            crate::commands::records_cmd::auto_group_campaigns,
             crate::commands::records_cmd::get_ticks,
             crate::commands::records_cmd::upsert_daily_memo,
             crate::commands::records_cmd::get_daily_memo,
+            crate::commands::records_cmd::get_trade_history,
+            crate::commands::records_cmd::get_daily_memo_history,
+            crate::commands::records_cmd::revert_change,
+            crate::commands::stream_cmd::stream_candles,
+            crate::commands::stream_cmd::stream_ticks,
+            crate::commands::settings_cmd::get_market_data_config,
+            crate::commands::settings_cmd::update_market_data_config,
+            crate::commands::settings_cmd::get_sync_scheduler_config,
+            crate::commands::settings_cmd::update_sync_scheduler_config,
+            crate::commands::settings_cmd::get_merge_config,
+            crate::commands::settings_cmd::update_merge_config,
+            crate::commands::settings_cmd::get_analysis_config,
+            crate::commands::settings_cmd::update_analysis_config,
+            crate::commands::settings_cmd::get_symbol_catalog,
+            crate::commands::settings_cmd::select_sync_symbols,
         ])
     };
 }
@@ -1,17 +1,35 @@
 use crate::db::DbState;
+use crate::models::db::audit::AuditEntry;
 use crate::models::db::candle::Candle;
+use crate::models::db::excursion::TradeExcursion;
 use crate::models::db::label::Label;
 use crate::models::db::record::Record;
 use crate::models::db::tick::Tick;
 use crate::models::db::trade::Trade;
 use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::service::campaign_summary::CampaignSummary;
 use crate::models::service::daily_summary::DailySummary;
+use crate::models::service::equity::{EquityCurve, EquityCurveOptions};
+use crate::models::service::excursion_compute::ExcursionComputeSummary;
+use crate::models::service::execution::ExecutionQuality;
+use crate::models::service::heatmap::TradeHeatmap;
+use crate::models::service::import_summary::ImportSummary;
 use crate::models::service::label_summary::LabelSummary;
+use crate::models::service::period_summary::{PeriodSummary, SummaryPeriod};
+use crate::models::service::post_exit::PostExitAnalysis;
+use crate::models::service::trade_context::TradeContext;
+use crate::models::service::trade_page::TradePage;
 use crate::models::service::trade_summary::TradeSummary;
-use tauri::State;
+use crate::models::service::trash::TrashedTrade;
+use crate::models::settings::market_data::DEFAULT_SYMBOL;
+use crate::models::settings::merge::MergeTimePolicy;
+use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
-pub fn insert_record(state: State<DbState>, csv_paths: Vec<String>) -> Result<(), String> {
+pub fn insert_record(
+    state: State<DbState>,
+    csv_paths: Vec<String>,
+) -> Result<ImportSummary, String> {
     let db = &*state;
     crate::service::import::import_csv_to_db(db, csv_paths)
 }
@@ -41,9 +59,30 @@ pub fn get_daily_records(state: State<DbState>) -> Result<Vec<DailySummary>, Str
 }
 
 #[tauri::command]
-pub fn get_candles(state: State<DbState>, interval: i64) -> Result<Vec<Candle>, String> {
+pub fn get_daily_summaries(state: State<DbState>) -> Result<Vec<DailySummary>, String> {
+    let db = &*state;
+    crate::service::records::fetch_daily_summaries(db)
+}
+
+#[tauri::command]
+pub fn get_period_records(
+    state: State<DbState>,
+    period: SummaryPeriod,
+    filter: Option<TradeFilter>,
+) -> Result<Vec<PeriodSummary>, String> {
+    let db = &*state;
+    crate::service::records::fetch_period_records(db, period, filter)
+}
+
+#[tauri::command]
+pub fn get_candles(
+    state: State<DbState>,
+    pair: Option<String>,
+    interval: i64,
+) -> Result<Vec<Candle>, String> {
     let db = &*state;
-    crate::service::candles::fetch_candles(db, interval)
+    let pair = pair.unwrap_or_else(|| DEFAULT_SYMBOL.to_string());
+    crate::service::candles::fetch_candles(db, &pair, interval)
 }
 
 #[tauri::command]
@@ -86,6 +125,12 @@ pub fn get_all_labels_with_trade(state: State<DbState>) -> Result<Vec<LabelSumma
     crate::service::labels::fetch_all_label_with_trade(db)
 }
 
+#[tauri::command]
+pub fn get_label_summaries(state: State<DbState>) -> Result<Vec<LabelSummary>, String> {
+    let db = &*state;
+    crate::service::labels::fetch_all_label_summaries(db)
+}
+
 #[tauri::command]
 pub fn update_memo(
     state: State<DbState>,
@@ -101,6 +146,42 @@ pub fn update_memo(
     crate::service::records::update_trade_memo_by_id(db, trade)
 }
 
+#[tauri::command]
+pub fn create_trade(state: State<DbState>, trade: Trade) -> Result<Trade, String> {
+    let db = &*state;
+    crate::service::trades::create_trade(db, trade)
+}
+
+#[tauri::command]
+pub fn update_trade(state: State<DbState>, trade: Trade) -> Result<Trade, String> {
+    let db = &*state;
+    crate::service::trades::update_trade(db, trade)
+}
+
+#[tauri::command]
+pub fn delete_trade(state: State<DbState>, id: i64) -> Result<(), String> {
+    let db = &*state;
+    crate::service::trades::delete_trade(db, id)
+}
+
+#[tauri::command]
+pub fn get_trash(state: State<DbState>) -> Result<Vec<TrashedTrade>, String> {
+    let db = &*state;
+    crate::service::trades::get_trash(db)
+}
+
+#[tauri::command]
+pub fn restore_trade(state: State<DbState>, id: i64) -> Result<Trade, String> {
+    let db = &*state;
+    crate::service::trades::restore_trade(db, id)
+}
+
+#[tauri::command]
+pub fn purge_trades(state: State<DbState>, ids: Vec<i64>) -> Result<usize, String> {
+    let db = &*state;
+    crate::service::trades::purge_trades(db, ids)
+}
+
 #[tauri::command]
 pub fn get_filtered_trades_summary(
     state: State<DbState>,
@@ -111,15 +192,57 @@ pub fn get_filtered_trades_summary(
 }
 
 #[tauri::command]
-pub fn merge_trades(state: State<DbState>, ids: Vec<i64>) -> Result<(), String> {
+pub fn get_filtered_summary(
+    state: State<DbState>,
+    filter: TradeFilter,
+) -> Result<TradeSummary, String> {
+    let db = &*state;
+    crate::service::trades::get_filtered_summary(db, filter)
+}
+
+#[tauri::command]
+pub fn get_trades_page(
+    state: State<DbState>,
+    filter: Option<TradeFilter>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> Result<TradePage, String> {
     let db = &*state;
-    crate::service::trades::merge_trades(db, ids)
+    crate::service::trades::get_trades_page(db, filter, offset, limit)
 }
 
 #[tauri::command]
-pub fn get_ticks(state: State<DbState>, from: i64, to: i64) -> Result<Vec<Tick>, String> {
+pub fn merge_trades(
+    state: State<DbState>,
+    ids: Vec<i64>,
+    time_policy: Option<MergeTimePolicy>,
+) -> Result<i64, String> {
     let db = &*state;
-    crate::service::ticks::fetch_ticks(db, from, to)
+    crate::service::trades::merge_trades(db, ids, time_policy)
+}
+
+#[tauri::command]
+pub fn unmerge_trade(state: State<DbState>, id: i64) -> Result<Vec<Trade>, String> {
+    let db = &*state;
+    crate::service::trades::unmerge_trade(db, id)
+}
+
+#[tauri::command]
+pub fn split_trade(state: State<DbState>, id: i64, lots: Vec<f64>) -> Result<Vec<Trade>, String> {
+    let db = &*state;
+    crate::service::trades::split_trade(db, id, lots)
+}
+
+#[tauri::command]
+pub fn get_ticks(
+    state: State<DbState>,
+    pair: Option<String>,
+    from: i64,
+    to: i64,
+) -> Result<Vec<Tick>, String> {
+    let db = &*state;
+    let pair = pair.unwrap_or_else(|| DEFAULT_SYMBOL.to_string());
+    crate::service::ticks::fetch_ticks(db, &pair, from, to)
 }
 
 #[tauri::command]
@@ -133,3 +256,171 @@ pub fn get_daily_memo(state: State<DbState>, date: String) -> Result<String, Str
     let db = &*state;
     crate::service::daily_memo::get_daily_memo(db, &date)
 }
+
+#[tauri::command]
+pub fn create_campaign(
+    state: State<DbState>,
+    name: Option<String>,
+    trade_ids: Vec<i64>,
+    memo: Option<String>,
+) -> Result<CampaignSummary, String> {
+    let db = &*state;
+    crate::service::campaigns::create_campaign(db, name, trade_ids, memo)
+}
+
+#[tauri::command]
+pub fn update_campaign(
+    state: State<DbState>,
+    id: i64,
+    name: String,
+    memo: String,
+) -> Result<(), String> {
+    let db = &*state;
+    crate::service::campaigns::update_campaign(db, id, name, memo)
+}
+
+#[tauri::command]
+pub fn delete_campaign(state: State<DbState>, id: i64) -> Result<(), String> {
+    let db = &*state;
+    crate::service::campaigns::delete_campaign(db, id)
+}
+
+#[tauri::command]
+pub fn add_trades_to_campaign(
+    state: State<DbState>,
+    id: i64,
+    trade_ids: Vec<i64>,
+) -> Result<CampaignSummary, String> {
+    let db = &*state;
+    crate::service::campaigns::add_trades(db, id, trade_ids)
+}
+
+#[tauri::command]
+pub fn remove_trades_from_campaign(
+    state: State<DbState>,
+    id: i64,
+    trade_ids: Vec<i64>,
+) -> Result<CampaignSummary, String> {
+    let db = &*state;
+    crate::service::campaigns::remove_trades(db, id, trade_ids)
+}
+
+#[tauri::command]
+pub fn get_campaign(state: State<DbState>, id: i64) -> Result<CampaignSummary, String> {
+    let db = &*state;
+    crate::service::campaigns::get_campaign(db, id)
+}
+
+#[tauri::command]
+pub fn get_campaigns(state: State<DbState>) -> Result<Vec<CampaignSummary>, String> {
+    let db = &*state;
+    crate::service::campaigns::get_campaigns(db)
+}
+
+#[tauri::command]
+pub fn auto_group_campaigns(state: State<DbState>) -> Result<Vec<CampaignSummary>, String> {
+    let db = &*state;
+    crate::service::campaigns::auto_group(db)
+}
+
+#[tauri::command]
+pub fn get_trade_history(state: State<DbState>, trade_id: i64) -> Result<Vec<AuditEntry>, String> {
+    let db = &*state;
+    crate::service::audit::get_trade_history(db, trade_id)
+}
+
+#[tauri::command]
+pub fn get_daily_memo_history(
+    state: State<DbState>,
+    date: String,
+) -> Result<Vec<AuditEntry>, String> {
+    let db = &*state;
+    crate::service::audit::get_daily_memo_history(db, &date)
+}
+
+#[tauri::command]
+pub fn revert_change(state: State<DbState>, audit_id: i64) -> Result<(), String> {
+    let db = &*state;
+    crate::service::audit::revert_change(db, audit_id)
+}
+
+/// 相場データを読む重い集計は、非同期ランタイムを止めないよう別スレッドで動かす
+async fn run_blocking<T: Send + 'static>(
+    app: AppHandle,
+    f: impl FnOnce(&DbState) -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(move || f(&app.state::<DbState>()))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[tauri::command]
+pub async fn compute_trade_excursions(
+    app: AppHandle,
+    recompute: Option<bool>,
+) -> Result<ExcursionComputeSummary, String> {
+    run_blocking(app, move |db| {
+        crate::service::excursions::compute_all(db, recompute.unwrap_or(false))
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn get_equity_curve(
+    state: State<DbState>,
+    filter: Option<TradeFilter>,
+    options: Option<EquityCurveOptions>,
+) -> Result<EquityCurve, String> {
+    let db = &*state;
+    crate::service::equity::get_equity_curve(db, filter, options)
+}
+
+#[tauri::command]
+pub fn get_trade_heatmap(
+    state: State<DbState>,
+    filter: Option<TradeFilter>,
+) -> Result<TradeHeatmap, String> {
+    let db = &*state;
+    crate::service::heatmap::analyze(db, filter)
+}
+
+#[tauri::command]
+pub async fn get_post_exit_analysis(
+    app: AppHandle,
+    filter: Option<TradeFilter>,
+    windows: Option<Vec<i64>>,
+) -> Result<PostExitAnalysis, String> {
+    run_blocking(app, move |db| {
+        crate::service::post_exit::analyze(db, filter, windows)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn get_execution_quality(
+    app: AppHandle,
+    filter: Option<TradeFilter>,
+) -> Result<ExecutionQuality, String> {
+    run_blocking(app, move |db| crate::service::execution::analyze(db, filter)).await
+}
+
+#[tauri::command]
+pub fn get_trade_excursion(
+    state: State<DbState>,
+    trade_id: i64,
+    recompute: Option<bool>,
+) -> Result<Option<TradeExcursion>, String> {
+    let db = &*state;
+    crate::service::excursions::get_excursion(db, trade_id, recompute.unwrap_or(false))
+}
+
+#[tauri::command]
+pub fn get_trade_context(
+    state: State<DbState>,
+    trade_id: i64,
+    interval: i64,
+    padding: Option<i64>,
+) -> Result<TradeContext, String> {
+    let db = &*state;
+    crate::service::trade_context::get_context(db, trade_id, interval, padding)
+}
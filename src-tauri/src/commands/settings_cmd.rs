@@ -0,0 +1,91 @@
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::db::symbol::SymbolSpec;
+use crate::models::settings::analysis::{AnalysisConfig, ANALYSIS_CONFIG_KEY};
+use crate::models::settings::market_data::{MarketDataConfig, MARKET_DATA_CONFIG_KEY};
+use crate::models::settings::merge::{MergeConfig, MERGE_CONFIG_KEY};
+use crate::models::settings::sync_scheduler::{SyncSchedulerConfig, SYNC_SCHEDULER_CONFIG_KEY};
+use crate::service::scheduler::SchedulerState;
+use tauri::State;
+
+#[tauri::command]
+pub fn get_market_data_config(market: State<MarketDataState>) -> Result<MarketDataConfig, String> {
+    market.config()
+}
+
+#[tauri::command]
+pub fn update_market_data_config(
+    state: State<DbState>,
+    market: State<MarketDataState>,
+    config: MarketDataConfig,
+) -> Result<(), String> {
+    let db = &*state;
+    config.validate()?;
+    crate::service::settings::save(db, MARKET_DATA_CONFIG_KEY, &config)?;
+    market.reload(config)
+}
+
+#[tauri::command]
+pub fn get_sync_scheduler_config(
+    scheduler: State<SchedulerState>,
+) -> Result<SyncSchedulerConfig, String> {
+    scheduler.config()
+}
+
+#[tauri::command]
+pub fn update_sync_scheduler_config(
+    state: State<DbState>,
+    scheduler: State<SchedulerState>,
+    config: SyncSchedulerConfig,
+) -> Result<(), String> {
+    let db = &*state;
+    config.validate()?;
+    crate::service::settings::save(db, SYNC_SCHEDULER_CONFIG_KEY, &config)?;
+    scheduler.update_config(config)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_merge_config(state: State<DbState>) -> Result<MergeConfig, String> {
+    let db = &*state;
+    crate::service::settings::load(db, MERGE_CONFIG_KEY)
+}
+
+#[tauri::command]
+pub fn update_merge_config(state: State<DbState>, config: MergeConfig) -> Result<(), String> {
+    let db = &*state;
+    config.validate()?;
+    crate::service::settings::save(db, MERGE_CONFIG_KEY, &config)
+}
+
+#[tauri::command]
+pub fn get_analysis_config(state: State<DbState>) -> Result<AnalysisConfig, String> {
+    let db = &*state;
+    crate::service::settings::load(db, ANALYSIS_CONFIG_KEY)
+}
+
+#[tauri::command]
+pub fn update_analysis_config(state: State<DbState>, config: AnalysisConfig) -> Result<(), String> {
+    let db = &*state;
+    config.validate()?;
+    crate::service::settings::save(db, ANALYSIS_CONFIG_KEY, &config)
+}
+
+#[tauri::command]
+pub fn get_symbol_catalog(
+    state: State<DbState>,
+    market: State<MarketDataState>,
+) -> Result<Vec<SymbolSpec>, String> {
+    let db = &*state;
+    crate::service::symbols::get_catalog(db, &market)
+}
+
+#[tauri::command]
+pub fn select_sync_symbols(
+    state: State<DbState>,
+    market: State<MarketDataState>,
+    symbols: Vec<String>,
+) -> Result<MarketDataConfig, String> {
+    let db = &*state;
+    crate::service::symbols::select_symbols(db, &market, symbols)
+}
@@ -0,0 +1,42 @@
+use crate::db::DbState;
+use crate::models::settings::market_data::DEFAULT_SYMBOL;
+use tauri::ipc::{Channel, InvokeResponseBody};
+use tauri::State;
+
+// 大量データはチャネル経由でバイナリチャンクとして送る
+// フォーマットは models::service::columnar を参照
+
+#[tauri::command]
+pub async fn stream_candles(
+    state: State<'_, DbState>,
+    pair: Option<String>,
+    interval: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+    on_chunk: Channel<InvokeResponseBody>,
+) -> Result<usize, String> {
+    let db = &*state;
+    let pair = pair.unwrap_or_else(|| DEFAULT_SYMBOL.to_string());
+    crate::service::stream::stream_candles(db, &pair, interval, from, to, |bytes| {
+        on_chunk
+            .send(InvokeResponseBody::Raw(bytes))
+            .map_err(|e| e.to_string())
+    })
+}
+
+#[tauri::command]
+pub async fn stream_ticks(
+    state: State<'_, DbState>,
+    pair: Option<String>,
+    from: i64,
+    to: i64,
+    on_chunk: Channel<InvokeResponseBody>,
+) -> Result<usize, String> {
+    let db = &*state;
+    let pair = pair.unwrap_or_else(|| DEFAULT_SYMBOL.to_string());
+    crate::service::stream::stream_ticks(db, &pair, from, to, |bytes| {
+        on_chunk
+            .send(InvokeResponseBody::Raw(bytes))
+            .map_err(|e| e.to_string())
+    })
+}
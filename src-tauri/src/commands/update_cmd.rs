@@ -1,5 +1,8 @@
+use tauri::Manager;
 use tauri_plugin_updater::UpdaterExt;
-use std::process::Command;
+
+use crate::market_data::MarketDataState;
+use crate::python_server::BridgeSupervisor;
 
 #[tauri::command]
 pub async fn check_for_updates(app: tauri::AppHandle) -> Result<Option<String>, String> {
@@ -24,9 +27,12 @@ pub async fn check_for_updates(app: tauri::AppHandle) -> Result<Option<String>,
 
 #[tauri::command]
 pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
-    let _ = stop_mt5_server();
     let updater = app.updater().map_err(|e| e.to_string())?;
     if let Some(update) = updater.check().await.map_err(|e| e.to_string())? {
+        // インストーラーが exe を置き換えられるようにブリッジを止めておく
+        let config = app.state::<MarketDataState>().config()?;
+        app.state::<BridgeSupervisor>().shutdown(&config).await;
+
         update
             .download_and_install(|_, _| {}, || {})
             .await
@@ -36,41 +42,3 @@ pub async fn install_update(app: tauri::AppHandle) -> Result<(), String> {
 
     Ok(())
 }
-
-fn stop_mt5_server() -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        let status = Command::new("taskkill")
-            .args(&["/F", "/IM", "mt5_server.exe"])
-            .status()
-            .map_err(|e| format!("MT5 終了失敗: {}", e))?;
-        if !status.success() {
-            return Err("MT5 終了コマンドが失敗しました".into());
-        }
-    }
-
-    #[cfg(target_os = "linux")]
-    {
-        let status = Command::new("pkill")
-            .arg("mt5_server")
-            .status()
-            .map_err(|e| format!("MT5 終了失敗: {}", e))?;
-        if !status.success() {
-            return Err("MT5 終了コマンドが失敗しました".into());
-        }
-    }
-
-    #[cfg(target_os = "macos")]
-    {
-        let status = Command::new("pkill")
-            .arg("-f")
-            .arg("mt5_server")
-            .status()
-            .map_err(|e| format!("MT5 終了失敗: {}", e))?;
-        if !status.success() {
-            return Err("MT5 終了コマンドが失敗しました".into());
-        }
-    }
-
-    Ok(())
-}
@@ -64,6 +64,276 @@ pub const MIGRATIONS: &[Migration] = &[
             PRAGMA foreign_keys = ON;
         "#,
     },
+    Migration {
+        version: "0.8.13",
+        sql: r#"
+            ALTER TABLE candles RENAME TO candles_old;
+
+            CREATE TABLE candles (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                pair TEXT NOT NULL,
+                timeframe TEXT NOT NULL DEFAULT 'M1',
+                time INTEGER NOT NULL,
+                open REAL NOT NULL,
+                high REAL NOT NULL,
+                low REAL NOT NULL,
+                close REAL NOT NULL,
+                tickvol INTEGER,
+                vol INTEGER,
+                spread INTEGER,
+                UNIQUE(pair, timeframe, time)
+            );
+
+            INSERT INTO candles (
+                id, pair, timeframe, time, open, high, low, close,
+                tickvol, vol, spread
+            )
+            SELECT
+                id,
+                CASE WHEN pair IN ('JPY/USD', 'JPYUSD') THEN 'USDJPY' ELSE pair END,
+                'M1', time, open, high, low, close,
+                tickvol, vol, spread
+            FROM candles_old;
+
+            DROP TABLE candles_old;
+
+            CREATE INDEX IF NOT EXISTS idx_candles_time ON candles(time);
+            CREATE INDEX IF NOT EXISTS idx_candles_pair_time ON candles(pair, timeframe, time);
+        "#,
+    },
+    Migration {
+        version: "0.8.14",
+        sql: r#"
+            PRAGMA foreign_keys = OFF;
+
+            ALTER TABLE trades RENAME TO trades_old;
+
+            CREATE TABLE trades (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                pair TEXT NOT NULL,
+                side TEXT NOT NULL,
+                lot REAL NOT NULL,
+                entry_rate REAL NOT NULL,
+                exit_rate REAL NOT NULL,
+                entry_time INTEGER NOT NULL,
+                exit_time INTEGER NOT NULL,
+                profit INTEGER NOT NULL,
+                profit_pips INTEGER NOT NULL,
+                swap INTEGER,
+                memo TEXT,
+                is_deleted INTEGER DEFAULT 0,
+                merged_to INTEGER,
+                account TEXT NOT NULL DEFAULT '',
+                split_from INTEGER NOT NULL DEFAULT 0,
+                split_index INTEGER NOT NULL DEFAULT 0,
+                UNIQUE(
+                    pair, side, lot,
+                    entry_time, exit_time,
+                    entry_rate, exit_rate,
+                    profit, profit_pips,
+                    swap, account,
+                    split_from, split_index
+                )
+            );
+
+            INSERT INTO trades (
+                id, pair, side, lot, entry_rate, exit_rate,
+                entry_time, exit_time, profit, profit_pips,
+                swap, memo, is_deleted, merged_to, account
+            )
+            SELECT
+                id, pair, side, lot, entry_rate, exit_rate,
+                entry_time, exit_time, profit, profit_pips,
+                swap, memo, is_deleted, merged_to, account
+            FROM trades_old;
+
+            DROP TABLE trades_old;
+
+            PRAGMA foreign_keys = ON;
+        "#,
+    },
+    // trades / trade_labels / daily_memo の変更を audit_log に記録する
+    // trades を作り直すマイグレーションではトリガーも消えるので作り直すこと
+    Migration {
+        version: "0.8.15",
+        sql: r#"
+            CREATE TRIGGER IF NOT EXISTS audit_trades_insert
+            AFTER INSERT ON trades
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'trades', 'INSERT', NEW.id, NEW.id,
+                    NULL,
+                    json_object(
+                        'id', NEW.id, 'pair', NEW.pair, 'side', NEW.side, 'lot', NEW.lot,
+                        'entry_rate', NEW.entry_rate, 'exit_rate', NEW.exit_rate,
+                        'entry_time', NEW.entry_time, 'exit_time', NEW.exit_time,
+                        'profit', NEW.profit, 'profit_pips', NEW.profit_pips, 'swap', NEW.swap,
+                        'memo', NEW.memo, 'is_deleted', NEW.is_deleted,
+                        'merged_to', NEW.merged_to, 'account', NEW.account,
+                        'split_from', NEW.split_from, 'split_index', NEW.split_index
+                    ),
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_trades_update
+            AFTER UPDATE ON trades
+            WHEN OLD.pair IS NOT NEW.pair
+              OR OLD.side IS NOT NEW.side
+              OR OLD.lot IS NOT NEW.lot
+              OR OLD.entry_rate IS NOT NEW.entry_rate
+              OR OLD.exit_rate IS NOT NEW.exit_rate
+              OR OLD.entry_time IS NOT NEW.entry_time
+              OR OLD.exit_time IS NOT NEW.exit_time
+              OR OLD.profit IS NOT NEW.profit
+              OR OLD.profit_pips IS NOT NEW.profit_pips
+              OR OLD.swap IS NOT NEW.swap
+              OR OLD.memo IS NOT NEW.memo
+              OR OLD.is_deleted IS NOT NEW.is_deleted
+              OR OLD.merged_to IS NOT NEW.merged_to
+              OR OLD.account IS NOT NEW.account
+              OR OLD.split_from IS NOT NEW.split_from
+              OR OLD.split_index IS NOT NEW.split_index
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'trades', 'UPDATE', NEW.id, NEW.id,
+                    json_object(
+                        'id', OLD.id, 'pair', OLD.pair, 'side', OLD.side, 'lot', OLD.lot,
+                        'entry_rate', OLD.entry_rate, 'exit_rate', OLD.exit_rate,
+                        'entry_time', OLD.entry_time, 'exit_time', OLD.exit_time,
+                        'profit', OLD.profit, 'profit_pips', OLD.profit_pips, 'swap', OLD.swap,
+                        'memo', OLD.memo, 'is_deleted', OLD.is_deleted,
+                        'merged_to', OLD.merged_to, 'account', OLD.account,
+                        'split_from', OLD.split_from, 'split_index', OLD.split_index
+                    ),
+                    json_object(
+                        'id', NEW.id, 'pair', NEW.pair, 'side', NEW.side, 'lot', NEW.lot,
+                        'entry_rate', NEW.entry_rate, 'exit_rate', NEW.exit_rate,
+                        'entry_time', NEW.entry_time, 'exit_time', NEW.exit_time,
+                        'profit', NEW.profit, 'profit_pips', NEW.profit_pips, 'swap', NEW.swap,
+                        'memo', NEW.memo, 'is_deleted', NEW.is_deleted,
+                        'merged_to', NEW.merged_to, 'account', NEW.account,
+                        'split_from', NEW.split_from, 'split_index', NEW.split_index
+                    ),
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_trades_delete
+            AFTER DELETE ON trades
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'trades', 'DELETE', OLD.id, OLD.id,
+                    json_object(
+                        'id', OLD.id, 'pair', OLD.pair, 'side', OLD.side, 'lot', OLD.lot,
+                        'entry_rate', OLD.entry_rate, 'exit_rate', OLD.exit_rate,
+                        'entry_time', OLD.entry_time, 'exit_time', OLD.exit_time,
+                        'profit', OLD.profit, 'profit_pips', OLD.profit_pips, 'swap', OLD.swap,
+                        'memo', OLD.memo, 'is_deleted', OLD.is_deleted,
+                        'merged_to', OLD.merged_to, 'account', OLD.account,
+                        'split_from', OLD.split_from, 'split_index', OLD.split_index
+                    ),
+                    NULL,
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_trade_labels_insert
+            AFTER INSERT ON trade_labels
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'trade_labels', 'INSERT', NEW.id, NEW.trade_id,
+                    NULL,
+                    json_object(
+                        'id', NEW.id, 'trade_id', NEW.trade_id, 'label_id', NEW.label_id
+                    ),
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_trade_labels_update
+            AFTER UPDATE ON trade_labels
+            WHEN OLD.trade_id IS NOT NEW.trade_id
+              OR OLD.label_id IS NOT NEW.label_id
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'trade_labels', 'UPDATE', NEW.id, NEW.trade_id,
+                    json_object(
+                        'id', OLD.id, 'trade_id', OLD.trade_id, 'label_id', OLD.label_id
+                    ),
+                    json_object(
+                        'id', NEW.id, 'trade_id', NEW.trade_id, 'label_id', NEW.label_id
+                    ),
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_trade_labels_delete
+            AFTER DELETE ON trade_labels
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'trade_labels', 'DELETE', OLD.id, OLD.trade_id,
+                    json_object(
+                        'id', OLD.id, 'trade_id', OLD.trade_id, 'label_id', OLD.label_id
+                    ),
+                    NULL,
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_daily_memo_insert
+            AFTER INSERT ON daily_memo
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'daily_memo', 'INSERT', NEW.date, NULL,
+                    NULL,
+                    json_object(
+                        'id', NEW.id, 'date', NEW.date, 'memo', NEW.memo
+                    ),
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_daily_memo_update
+            AFTER UPDATE ON daily_memo
+            WHEN OLD.date IS NOT NEW.date
+              OR OLD.memo IS NOT NEW.memo
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'daily_memo', 'UPDATE', NEW.date, NULL,
+                    json_object(
+                        'id', OLD.id, 'date', OLD.date, 'memo', OLD.memo
+                    ),
+                    json_object(
+                        'id', NEW.id, 'date', NEW.date, 'memo', NEW.memo
+                    ),
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS audit_daily_memo_delete
+            AFTER DELETE ON daily_memo
+            BEGIN
+                INSERT INTO audit_log (table_name, operation, row_key, trade_id, before, after, changed_at)
+                VALUES (
+                    'daily_memo', 'DELETE', OLD.date, NULL,
+                    json_object(
+                        'id', OLD.id, 'date', OLD.date, 'memo', OLD.memo
+                    ),
+                    NULL,
+                    CAST(strftime('%s', 'now') AS INTEGER)
+                );
+            END;
+        "#,
+    },
 ];
 
 pub fn run_migrations(state: &DbState) -> Result<(), String> {
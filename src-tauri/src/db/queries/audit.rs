@@ -0,0 +1,199 @@
+use rusqlite::{params, Result, Row, Transaction};
+
+use crate::db::DbState;
+use crate::models::db::audit::AuditEntry;
+
+// 履歴から戻すときに書き戻す列
+const TRADES_COLUMNS: &[&str] = &[
+    "id", "pair", "side", "lot", "entry_rate", "exit_rate", "entry_time", "exit_time", "profit",
+    "profit_pips", "swap", "memo", "is_deleted", "merged_to", "account", "split_from",
+    "split_index",
+];
+// 取引の削除・マージ・分割の状態。履歴から戻すときは書き戻さず、今の行と一致するか確かめる
+const TRADES_LINEAGE_COLUMNS: &[&str] = &["is_deleted", "merged_to", "split_from", "split_index"];
+const TRADE_LABELS_COLUMNS: &[&str] = &["id", "trade_id", "label_id"];
+const DAILY_MEMO_COLUMNS: &[&str] = &["id", "date", "memo"];
+
+const SELECT_AUDIT: &str = "SELECT id, table_name, operation, row_key, trade_id, before, after, changed_at
+     FROM audit_log";
+
+/// 取引とそのラベルの変更履歴（新しい順）
+pub fn find_by_trade_id(state: &DbState, trade_id: i64) -> Result<Vec<AuditEntry>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE trade_id = ?1 ORDER BY id DESC",
+            SELECT_AUDIT
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![trade_id], to_entry)
+        .map_err(|e| e.to_string())?;
+
+    let mut entries = Vec::new();
+    for r in rows {
+        entries.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(entries)
+}
+
+/// 日別メモの変更履歴（新しい順）
+pub fn find_by_daily_memo(state: &DbState, date: &str) -> Result<Vec<AuditEntry>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!(
+            "{} WHERE table_name = 'daily_memo' AND row_key = ?1 ORDER BY id DESC",
+            SELECT_AUDIT
+        ))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![date], to_entry)
+        .map_err(|e| e.to_string())?;
+
+    let mut entries = Vec::new();
+    for r in rows {
+        entries.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(entries)
+}
+
+pub fn find_by_id(state: &DbState, id: i64) -> Result<Option<AuditEntry>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{} WHERE id = ?1", SELECT_AUDIT))
+        .map_err(|e| e.to_string())?;
+
+    let mut rows = stmt.query_map([id], to_entry).map_err(|e| e.to_string())?;
+    match rows.next() {
+        Some(r) => Ok(Some(r.map_err(|e| e.to_string())?)),
+        None => Ok(None),
+    }
+}
+
+/// 行を JSON の内容に書き戻す。行がなければ作り直す
+/// 取引は削除・マージ・分割の状態が今の行と同じときだけ、それ以外の列を書き戻す
+/// 書き戻しもトリガーで履歴に残る
+pub fn restore_row(state: &DbState, table_name: &str, row: &str) -> Result<(), String> {
+    let (columns, key) = columns_of(table_name)?;
+    let is_trade = table_name == "trades";
+
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    if is_trade && !same_lineage(&tx, row)? {
+        return Err(
+            "取引がその後削除・マージ・分割されているため履歴から戻せません".to_string(),
+        );
+    }
+
+    let assignments = columns
+        .iter()
+        .filter(|c| **c != key)
+        .filter(|c| !is_trade || !TRADES_LINEAGE_COLUMNS.contains(c))
+        .map(|c| format!("{} = json_extract(?1, '$.{}')", c, c))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let updated = tx
+        .execute(
+            &format!(
+                "UPDATE {} SET {} WHERE {} = json_extract(?1, '$.{}')",
+                table_name, assignments, key, key
+            ),
+            params![row],
+        )
+        .map_err(|e| e.to_string())?;
+
+    if is_trade {
+        // 内容が変わったので計算し直す
+        tx.execute(
+            "DELETE FROM trade_excursions WHERE trade_id = json_extract(?1, '$.id')",
+            params![row],
+        )
+        .map_err(|e| e.to_string())?;
+    } else if updated == 0 {
+        let values = columns
+            .iter()
+            .map(|c| format!("json_extract(?1, '$.{}')", c))
+            .collect::<Vec<_>>()
+            .join(", ");
+        tx.execute(
+            &format!(
+                "INSERT INTO {} ({}) VALUES ({})",
+                table_name,
+                columns.join(", "),
+                values
+            ),
+            params![row],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 取引の行があり、削除・マージ・分割の状態が JSON と同じか
+fn same_lineage(tx: &Transaction, row: &str) -> Result<bool, String> {
+    let conditions = TRADES_LINEAGE_COLUMNS
+        .iter()
+        .map(|c| format!("COALESCE({}, 0) = COALESCE(json_extract(?1, '$.{}'), 0)", c, c))
+        .collect::<Vec<_>>()
+        .join(" AND ");
+    tx.query_row(
+        &format!(
+            "SELECT COUNT(*) FROM trades WHERE id = json_extract(?1, '$.id') AND {}",
+            conditions
+        ),
+        params![row],
+        |r| r.get::<_, i64>(0),
+    )
+    .map(|n| n > 0)
+    .map_err(|e| e.to_string())
+}
+
+/// 追加された行を取り消す。取引は削除済みにするだけで行は残す
+pub fn remove_row(state: &DbState, table_name: &str, row: &str) -> Result<(), String> {
+    let (_, key) = columns_of(table_name)?;
+
+    let sql = if table_name == "trades" {
+        "UPDATE trades SET is_deleted = 1 WHERE id = json_extract(?1, '$.id')".to_string()
+    } else {
+        format!(
+            "DELETE FROM {} WHERE {} = json_extract(?1, '$.{}')",
+            table_name, key, key
+        )
+    };
+
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(&sql, params![row])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// テーブルの列と、行を特定する列
+fn columns_of(table_name: &str) -> Result<(&'static [&'static str], &'static str), String> {
+    match table_name {
+        "trades" => Ok((TRADES_COLUMNS, "id")),
+        "trade_labels" => Ok((TRADE_LABELS_COLUMNS, "id")),
+        "daily_memo" => Ok((DAILY_MEMO_COLUMNS, "date")),
+        _ => Err(format!("履歴から戻せないテーブルです: {}", table_name)),
+    }
+}
+
+fn to_entry(row: &Row) -> Result<AuditEntry> {
+    let before: Option<String> = row.get(5)?;
+    let after: Option<String> = row.get(6)?;
+
+    Ok(AuditEntry {
+        id: row.get(0)?,
+        table_name: row.get(1)?,
+        operation: row.get(2)?,
+        row_key: row.get(3)?,
+        trade_id: row.get(4)?,
+        before: before.and_then(|s| serde_json::from_str(&s).ok()),
+        after: after.and_then(|s| serde_json::from_str(&s).ok()),
+        changed_at: row.get(7)?,
+    })
+}
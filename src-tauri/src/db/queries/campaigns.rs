@@ -0,0 +1,149 @@
+use rusqlite::{params, params_from_iter, Result, Row, Transaction};
+
+use crate::db::DbState;
+use crate::models::db::campaign::Campaign;
+
+/// キャンペーンを追加して取引を紐付け、ID を返す。途中で失敗したら何も残さない
+pub fn insert_campaign_with_trades(
+    state: &DbState,
+    campaign: &Campaign,
+    trade_ids: &[i64],
+) -> Result<i64, String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let id = insert_campaign_tx(&tx, campaign)?;
+    add_trades_tx(&tx, id, trade_ids)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+fn insert_campaign_tx(tx: &Transaction, campaign: &Campaign) -> Result<i64, String> {
+    tx.execute(
+        "INSERT INTO campaigns (name, pair, side, memo, auto, created_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            campaign.name,
+            campaign.pair,
+            campaign.side,
+            campaign.memo,
+            campaign.auto,
+            campaign.created_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(tx.last_insert_rowid())
+}
+
+pub fn update_campaign(state: &DbState, campaign: &Campaign) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE campaigns SET name = ?1, memo = ?2 WHERE id = ?3",
+        params![campaign.name, campaign.memo, campaign.id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// キャンペーンと取引の紐付けを削除する。取引は残す
+pub fn delete_campaign(state: &DbState, id: i64) -> Result<(), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    tx.execute("DELETE FROM campaign_trades WHERE campaign_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM campaigns WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn get_all_campaigns(state: &DbState) -> Result<Vec<Campaign>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, pair, side, COALESCE(memo, ''), auto, created_at
+             FROM campaigns
+             ORDER BY created_at DESC, id DESC",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([], to_campaign).map_err(|e| e.to_string())?;
+
+    let mut campaigns = Vec::new();
+    for r in rows {
+        campaigns.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(campaigns)
+}
+
+pub fn find_by_id(state: &DbState, id: i64) -> Result<Option<Campaign>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, pair, side, COALESCE(memo, ''), auto, created_at
+             FROM campaigns
+             WHERE id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut rows = stmt.query_map([id], to_campaign).map_err(|e| e.to_string())?;
+    match rows.next() {
+        Some(r) => Ok(Some(r.map_err(|e| e.to_string())?)),
+        None => Ok(None),
+    }
+}
+
+pub fn add_trades(state: &DbState, campaign_id: i64, trade_ids: &[i64]) -> Result<(), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    add_trades_tx(&tx, campaign_id, trade_ids)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+fn add_trades_tx(tx: &Transaction, campaign_id: i64, trade_ids: &[i64]) -> Result<(), String> {
+    let mut stmt = tx
+        .prepare("INSERT OR IGNORE INTO campaign_trades (campaign_id, trade_id) VALUES (?1, ?2)")
+        .map_err(|e| e.to_string())?;
+    for trade_id in trade_ids {
+        stmt.execute(params![campaign_id, trade_id])
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+pub fn remove_trades(state: &DbState, campaign_id: i64, trade_ids: &[i64]) -> Result<(), String> {
+    if trade_ids.is_empty() {
+        return Ok(());
+    }
+
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let placeholders = std::iter::repeat("?")
+        .take(trade_ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let sql = format!(
+        "DELETE FROM campaign_trades WHERE campaign_id = {} AND trade_id IN ({})",
+        campaign_id, placeholders
+    );
+
+    conn.execute(&sql, params_from_iter(trade_ids.iter()))
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+fn to_campaign(row: &Row) -> Result<Campaign> {
+    Ok(Campaign {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        pair: row.get(2)?,
+        side: row.get(3)?,
+        memo: row.get(4)?,
+        auto: row.get(5)?,
+        created_at: row.get(6)?,
+    })
+}
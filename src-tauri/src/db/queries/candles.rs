@@ -3,12 +3,12 @@ use rusqlite::{params, Result};
 use crate::db::DbState;
 use crate::models::db::candle::Candle;
 
-pub fn get_latest_time(state: &DbState) -> Result<i64, String> {
+pub fn get_latest_time(state: &DbState, pair: &str, timeframe: &str) -> Result<i64, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
 
     let latest_time: Result<i64, _> = conn.query_row(
-        "SELECT time FROM candles ORDER BY time DESC LIMIT 1",
-        [],
+        "SELECT time FROM candles WHERE pair = ?1 AND timeframe = ?2 ORDER BY time DESC LIMIT 1",
+        params![pair, timeframe],
         |row| row.get(0),
     );
 
@@ -19,54 +19,79 @@ pub fn get_latest_time(state: &DbState) -> Result<i64, String> {
     }
 }
 
-pub fn insert_candle(state: &DbState, candle: Candle) -> Result<(), String> {
-    let state = state.conn.lock().map_err(|e| e.to_string())?;
-    state
-        .execute(
-            "INSERT OR IGNORE INTO candles (
-        type, time, open, high, low, close, tickvol, vol, spread
-        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
-            params![
-                "JPYUSD",
-                candle.time,
-                candle.open,
-                candle.high,
-                candle.low,
-                candle.close,
-                candle.tickvol,
-                candle.vol,
-                candle.spread
-            ],
-        )
-        .map_err(|e| e.to_string())?;
-    Ok(())
-}
-
-pub fn insert_candles_bulk(state: &DbState, candles: &Vec<Candle>) -> Result<(), String> {
+pub fn insert_candles_bulk(
+    state: &DbState,
+    timeframe: &str,
+    candles: &Vec<Candle>,
+) -> Result<usize, String> {
     if candles.is_empty() {
-        return Ok(());
+        return Ok(0);
     }
 
     let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
     let tx = conn.transaction().map_err(|e| e.to_string())?;
 
+    // INSERT OR IGNORE で実際に追加された件数
+    let mut inserted = 0;
     {
         let mut stmt = tx
             .prepare(
                 "INSERT OR IGNORE INTO candles
-                (time, open, high, low, close, tickvol, vol, spread, pair)
-                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                (time, open, high, low, close, tickvol, vol, spread, pair, timeframe)
+                VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
             )
             .map_err(|e| e.to_string())?;
 
         for c in candles {
-            stmt.execute(params![
-                c.time, c.open, c.high, c.low, c.close, c.tickvol, c.vol, c.spread, c.pair
+            inserted += stmt.execute(params![
+                c.time, c.open, c.high, c.low, c.close, c.tickvol, c.vol, c.spread, c.pair,
+                timeframe
             ])
             .map_err(|e| e.to_string())?;
         }
     }
 
     tx.commit().map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(inserted)
+}
+
+/// pair・timeframe の [from, to) の範囲のローソク足を時刻昇順で最大 limit 件取得する
+pub fn find_candles_page(
+    state: &DbState,
+    pair: &str,
+    timeframe: &str,
+    from: i64,
+    to: i64,
+    limit: i64,
+) -> Result<Vec<Candle>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let sql = r#"
+        SELECT pair, time, open, high, low, close
+        FROM candles
+        WHERE pair = ?1 AND timeframe = ?2 AND time >= ?3 AND time < ?4
+        ORDER BY time ASC
+        LIMIT ?5
+    "#;
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![pair, timeframe, from, to, limit], |row| {
+            Ok(Candle {
+                pair: row.get(0)?,
+                time: row.get(1)?,
+                open: row.get(2)?,
+                high: row.get(3)?,
+                low: row.get(4)?,
+                close: row.get(5)?,
+                ..Default::default()
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut candles = Vec::new();
+    for r in rows {
+        candles.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(candles)
 }
@@ -0,0 +1,183 @@
+use rusqlite::{params, Result};
+
+use crate::db::DbState;
+use crate::models::db::deal::{Deal, DEAL_ENTRY_INOUT, DEAL_ENTRY_OUT, DEAL_ENTRY_OUT_BY};
+
+/// 取り込み済みの最新約定時刻。未取り込みなら 0（全履歴）
+pub fn get_latest_time(state: &DbState) -> Result<i64, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.query_row("SELECT COALESCE(MAX(time), 0) FROM mt5_deals", [], |row| {
+        row.get(0)
+    })
+    .map_err(|e| e.to_string())
+}
+
+/// チケットが未登録の約定だけ追加し、追加したチケットを返す
+pub fn insert_deals_bulk(state: &DbState, deals: &Vec<Deal>) -> Result<Vec<i64>, String> {
+    if deals.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let mut inserted = Vec::new();
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT OR IGNORE INTO mt5_deals
+                (ticket, position_id, order_ticket, symbol, type, entry, volume, price,
+                 profit, swap, commission, time, time_msc, magic, comment)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+            )
+            .map_err(|e| e.to_string())?;
+
+        for d in deals {
+            let changed = stmt
+                .execute(params![
+                    d.ticket,
+                    d.position_id,
+                    d.order,
+                    d.symbol,
+                    d.deal_type,
+                    d.entry,
+                    d.volume,
+                    d.price,
+                    d.profit,
+                    d.swap,
+                    d.commission,
+                    d.time,
+                    d.time_msc,
+                    d.magic,
+                    d.comment
+                ])
+                .map_err(|e| e.to_string())?;
+            if changed > 0 {
+                inserted.push(d.ticket);
+            }
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(inserted)
+}
+
+/// ポジションに属する約定を約定順に返す
+pub fn find_by_position(state: &DbState, position_id: i64) -> Result<Vec<Deal>, String> {
+    find_deals(state, "WHERE position_id = ?1", params![position_id])
+}
+
+/// 決済約定から取引を作れていないポジション
+pub fn find_unlinked_exit_positions(state: &DbState) -> Result<Vec<i64>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT position_id FROM mt5_deals
+            WHERE entry IN (?1, ?2, ?3) AND trade_id IS NULL
+            ORDER BY position_id",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(
+            params![DEAL_ENTRY_OUT, DEAL_ENTRY_INOUT, DEAL_ENTRY_OUT_BY],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut ids = Vec::new();
+    for r in rows {
+        ids.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(ids)
+}
+
+/// すべての約定をポジション・約定順に返す
+pub fn get_all_deals(state: &DbState) -> Result<Vec<Deal>, String> {
+    find_deals(state, "", params![])
+}
+
+/// 決済約定から作ったトレードごとの手数料
+/// 新規約定の手数料は、同じポジションの新規ロットに対する決済ロットの割合で按分する
+pub fn get_commissions_by_trade(state: &DbState) -> Result<Vec<(i64, f64)>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let sql = r#"
+        SELECT x.trade_id,
+               x.commission + COALESCE(
+                   e.commission * MIN(x.volume / NULLIF(e.volume, 0), 1.0), 0)
+        FROM mt5_deals x
+        LEFT JOIN (
+            SELECT position_id, SUM(commission) AS commission, SUM(volume) AS volume
+            FROM mt5_deals
+            WHERE entry = 0
+            GROUP BY position_id
+        ) e ON e.position_id = x.position_id
+        WHERE x.trade_id IS NOT NULL
+    "#;
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut fees = Vec::new();
+    for r in rows {
+        fees.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(fees)
+}
+
+fn find_deals(
+    state: &DbState,
+    condition: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Vec<Deal>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT ticket, position_id, order_ticket, symbol, type, entry, volume, price,
+                profit, swap, commission, time, time_msc, magic, comment, trade_id
+        FROM mt5_deals
+        {}
+        ORDER BY position_id, time_msc, ticket",
+        condition
+    );
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params, |row| {
+            Ok(Deal {
+                ticket: row.get(0)?,
+                position_id: row.get(1)?,
+                order: row.get(2)?,
+                symbol: row.get(3)?,
+                deal_type: row.get(4)?,
+                entry: row.get(5)?,
+                volume: row.get(6)?,
+                price: row.get(7)?,
+                profit: row.get(8)?,
+                swap: row.get(9)?,
+                commission: row.get(10)?,
+                time: row.get(11)?,
+                time_msc: row.get(12)?,
+                magic: row.get(13)?,
+                comment: row.get(14)?,
+                trade_id: row.get(15)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut deals = Vec::new();
+    for r in rows {
+        deals.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(deals)
+}
+
+pub fn update_trade_id(state: &DbState, ticket: i64, trade_id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE mt5_deals SET trade_id = ?1 WHERE ticket = ?2",
+        params![trade_id, ticket],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
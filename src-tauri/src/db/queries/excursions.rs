@@ -0,0 +1,115 @@
+use rusqlite::{params, Result, Row};
+use std::collections::{HashMap, HashSet};
+
+use crate::db::DbState;
+use crate::models::db::excursion::TradeExcursion;
+
+pub fn upsert_excursion(state: &DbState, excursion: &TradeExcursion) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO trade_excursions (
+            trade_id, source, mae_pips, mfe_pips, mae_yen, mfe_yen, mae_secs, mfe_secs, computed_at
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)
+        ON CONFLICT(trade_id) DO UPDATE SET
+            source = excluded.source,
+            mae_pips = excluded.mae_pips,
+            mfe_pips = excluded.mfe_pips,
+            mae_yen = excluded.mae_yen,
+            mfe_yen = excluded.mfe_yen,
+            mae_secs = excluded.mae_secs,
+            mfe_secs = excluded.mfe_secs,
+            computed_at = excluded.computed_at",
+        params![
+            excursion.trade_id,
+            excursion.source,
+            excursion.mae_pips,
+            excursion.mfe_pips,
+            excursion.mae_yen,
+            excursion.mfe_yen,
+            excursion.mae_secs,
+            excursion.mfe_secs,
+            excursion.computed_at,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn delete_by_trade_id(state: &DbState, trade_id: i64) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM trade_excursions WHERE trade_id = ?1",
+        params![trade_id],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+pub fn find_by_trade_id(state: &DbState, trade_id: i64) -> Result<Option<TradeExcursion>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT trade_id, source, mae_pips, mfe_pips, mae_yen, mfe_yen, mae_secs, mfe_secs, computed_at
+             FROM trade_excursions
+             WHERE trade_id = ?1",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut rows = stmt
+        .query_map(params![trade_id], to_excursion)
+        .map_err(|e| e.to_string())?;
+    match rows.next() {
+        Some(r) => Ok(Some(r.map_err(|e| e.to_string())?)),
+        None => Ok(None),
+    }
+}
+
+/// 計算済みの MAE/MFE を trade_id で引けるようにまとめて読む
+/// 取引の数だけ変数を並べると SQLite の上限を超えるので、絞り込まずに読む
+pub fn get_all_by_trade_id(state: &DbState) -> Result<HashMap<i64, TradeExcursion>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT trade_id, source, mae_pips, mfe_pips, mae_yen, mfe_yen, mae_secs, mfe_secs, computed_at
+             FROM trade_excursions",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = stmt.query_map([], to_excursion).map_err(|e| e.to_string())?;
+
+    let mut excursions = HashMap::new();
+    for r in rows {
+        let e = r.map_err(|e| e.to_string())?;
+        excursions.insert(e.trade_id, e);
+    }
+    Ok(excursions)
+}
+
+/// MAE/MFE を計算済みの trade_id
+pub fn get_computed_trade_ids(state: &DbState) -> Result<HashSet<i64>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT trade_id FROM trade_excursions")
+        .map_err(|e| e.to_string())?;
+
+    let ids = stmt
+        .query_map([], |row| row.get::<_, i64>(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashSet<i64>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    Ok(ids)
+}
+
+fn to_excursion(row: &Row) -> Result<TradeExcursion> {
+    Ok(TradeExcursion {
+        trade_id: row.get(0)?,
+        source: row.get(1)?,
+        mae_pips: row.get(2)?,
+        mfe_pips: row.get(3)?,
+        mae_yen: row.get(4)?,
+        mfe_yen: row.get(5)?,
+        mae_secs: row.get(6)?,
+        mfe_secs: row.get(7)?,
+        computed_at: row.get(8)?,
+    })
+}
@@ -1,7 +1,12 @@
+pub mod audit;
+pub mod campaigns;
 pub mod candles;
+pub mod deals;
+pub mod excursions;
 pub mod labels;
 pub mod meta;
 pub mod records;
+pub mod symbols;
 pub mod trade_label;
 pub mod trades;
 pub mod ticks;
@@ -43,6 +43,7 @@ pub fn get_all_records(state: &DbState) -> Result<Vec<Record>, String> {
                 profit: row.get(6)?,
                 swap: row.get(7)?,
                 order_time: row.get(8)?,
+                ..Default::default()
             })
         })
         .map_err(|e| e.to_string())?;
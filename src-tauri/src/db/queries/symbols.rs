@@ -0,0 +1,136 @@
+use rusqlite::{params, Result, Row};
+
+use crate::db::DbState;
+use crate::models::db::symbol::SymbolSpec;
+
+/// 取得したカタログで上書きする。ブローカーが取り扱いをやめた銘柄は残す
+pub fn upsert_symbols_bulk(state: &DbState, symbols: &Vec<SymbolSpec>) -> Result<usize, String> {
+    if symbols.is_empty() {
+        return Ok(0);
+    }
+
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let mut upserted = 0;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO symbols (
+                    name, description, path, currency_base, currency_profit, digits, point,
+                    contract_size, volume_min, volume_max, volume_step, swap_long, swap_short,
+                    swap_mode, trade_mode, sessions, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17)
+                ON CONFLICT(name) DO UPDATE SET
+                    description = excluded.description,
+                    path = excluded.path,
+                    currency_base = excluded.currency_base,
+                    currency_profit = excluded.currency_profit,
+                    digits = excluded.digits,
+                    point = excluded.point,
+                    contract_size = excluded.contract_size,
+                    volume_min = excluded.volume_min,
+                    volume_max = excluded.volume_max,
+                    volume_step = excluded.volume_step,
+                    swap_long = excluded.swap_long,
+                    swap_short = excluded.swap_short,
+                    swap_mode = excluded.swap_mode,
+                    trade_mode = excluded.trade_mode,
+                    -- 取引時間帯は表示中の銘柄しか取得しないので、空なら前回の値を残す
+                    sessions = CASE WHEN excluded.sessions = '[]'
+                        THEN COALESCE(symbols.sessions, '[]') ELSE excluded.sessions END,
+                    updated_at = excluded.updated_at",
+            )
+            .map_err(|e| e.to_string())?;
+
+        for s in symbols {
+            let sessions = serde_json::to_string(&s.sessions).map_err(|e| e.to_string())?;
+            upserted += stmt
+                .execute(params![
+                    s.name,
+                    s.description,
+                    s.path,
+                    s.currency_base,
+                    s.currency_profit,
+                    s.digits,
+                    s.point,
+                    s.contract_size,
+                    s.volume_min,
+                    s.volume_max,
+                    s.volume_step,
+                    s.swap_long,
+                    s.swap_short,
+                    s.swap_mode,
+                    s.trade_mode,
+                    sessions,
+                    s.updated_at
+                ])
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(upserted)
+}
+
+pub fn get_all_symbols(state: &DbState) -> Result<Vec<SymbolSpec>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(&format!("{} ORDER BY path, name", SELECT_SYMBOLS))
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt.query_map([], to_symbol).map_err(|e| e.to_string())?;
+
+    let mut symbols = Vec::new();
+    for r in rows {
+        symbols.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(symbols)
+}
+
+pub fn find_by_name(state: &DbState, name: &str) -> Result<Option<SymbolSpec>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let symbol = conn.query_row(
+        &format!("{} WHERE name = ?1", SELECT_SYMBOLS),
+        params![name],
+        to_symbol,
+    );
+
+    match symbol {
+        Ok(s) => Ok(Some(s)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+const SELECT_SYMBOLS: &str = "
+    SELECT name, description, path, currency_base, currency_profit, digits, point,
+           contract_size, volume_min, volume_max, volume_step, swap_long, swap_short,
+           swap_mode, trade_mode, sessions, updated_at
+    FROM symbols";
+
+fn to_symbol(row: &Row) -> rusqlite::Result<SymbolSpec> {
+    let sessions: Option<String> = row.get(15)?;
+    Ok(SymbolSpec {
+        name: row.get(0)?,
+        description: row.get::<_, Option<String>>(1)?.unwrap_or_default(),
+        path: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+        currency_base: row.get::<_, Option<String>>(3)?.unwrap_or_default(),
+        currency_profit: row.get::<_, Option<String>>(4)?.unwrap_or_default(),
+        digits: row.get(5)?,
+        point: row.get(6)?,
+        contract_size: row.get(7)?,
+        volume_min: row.get::<_, Option<f64>>(8)?.unwrap_or_default(),
+        volume_max: row.get::<_, Option<f64>>(9)?.unwrap_or_default(),
+        volume_step: row.get::<_, Option<f64>>(10)?.unwrap_or_default(),
+        swap_long: row.get::<_, Option<f64>>(11)?.unwrap_or_default(),
+        swap_short: row.get::<_, Option<f64>>(12)?.unwrap_or_default(),
+        swap_mode: row.get::<_, Option<i32>>(13)?.unwrap_or_default(),
+        trade_mode: row.get::<_, Option<i32>>(14)?.unwrap_or_default(),
+        sessions: sessions
+            .and_then(|s| serde_json::from_str(&s).ok())
+            .unwrap_or_default(),
+        updated_at: row.get(16)?,
+        selected: false,
+    })
+}
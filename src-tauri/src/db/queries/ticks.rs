@@ -3,12 +3,12 @@ use rusqlite::{params, Result};
 use crate::db::DbState;
 use crate::models::db::tick::Tick;
 
-pub fn get_latest_time(state: &DbState) -> Result<i64, String> {
+pub fn get_latest_time(state: &DbState, pair: &str) -> Result<i64, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
 
     let latest_time: Result<i64, _> = conn.query_row(
-        "SELECT time FROM ticks ORDER BY time DESC LIMIT 1",
-        [],
+        "SELECT time FROM ticks WHERE pair = ?1 ORDER BY time DESC LIMIT 1",
+        params![pair],
         |row| row.get(0),
     );
 
@@ -19,15 +19,17 @@ pub fn get_latest_time(state: &DbState) -> Result<i64, String> {
     }
 }
 
-pub fn insert_ticks_bulk(state: &DbState, ticks: &Vec<Tick>) -> Result<(), String> {
+pub fn insert_ticks_bulk(state: &DbState, ticks: &Vec<Tick>) -> Result<usize, String> {
     println!("{}", ticks.len());
     if ticks.is_empty() {
-        return Ok(());
+        return Ok(0);
     }
 
     let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
     let tx = conn.transaction().map_err(|e| e.to_string())?;
 
+    // INSERT OR IGNORE で実際に追加された件数
+    let mut inserted = 0;
     {
         let mut stmt = tx
             .prepare(
@@ -38,7 +40,7 @@ pub fn insert_ticks_bulk(state: &DbState, ticks: &Vec<Tick>) -> Result<(), Strin
             .map_err(|e| e.to_string())?;
 
         for t in ticks {
-            stmt.execute(params![
+            inserted += stmt.execute(params![
                 t.time, t.time_msc, t.ask, t.bid, t.pair
             ])
             .map_err(|e| e.to_string())?;
@@ -46,22 +48,27 @@ pub fn insert_ticks_bulk(state: &DbState, ticks: &Vec<Tick>) -> Result<(), Strin
     }
 
     tx.commit().map_err(|e| e.to_string())?;
-    Ok(())
+    Ok(inserted)
 }
 
-pub fn find_tick_from_unixtime(state: &DbState, from: i64, to: i64) -> Result<Vec<Tick>, String> {
+pub fn find_tick_from_unixtime(
+    state: &DbState,
+    pair: &str,
+    from: i64,
+    to: i64,
+) -> Result<Vec<Tick>, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
 
     let sql = r#"
         SELECT pair, time, time_msc, bid, ask
         FROM ticks
-        WHERE time_msc BETWEEN ?1 AND ?2
+        WHERE pair = ?1 AND time_msc BETWEEN ?2 AND ?3
         ORDER BY time_msc ASC
     "#;
 
     let mut stmt = conn.prepare(sql).map_err(|e| format!("prepare error: {}", e))?;
     let rows = stmt
-        .query_map(params![from, to], |row| {
+        .query_map(params![pair, from, to], |row| {
             Ok(Tick {
                 pair: row.get(0)?,
                 time: row.get(1)?,
@@ -80,3 +87,132 @@ pub fn find_tick_from_unixtime(state: &DbState, from: i64, to: i64) -> Result<Ve
 
     Ok(ticks)
 }
+
+/// pair の [from, to] の範囲のティックを time_msc 昇順で最大 limit 件取得する
+pub fn find_ticks_page(
+    state: &DbState,
+    pair: &str,
+    from: i64,
+    to: i64,
+    limit: i64,
+) -> Result<Vec<Tick>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let sql = r#"
+        SELECT pair, time, time_msc, bid, ask
+        FROM ticks
+        WHERE pair = ?1 AND time_msc BETWEEN ?2 AND ?3
+        ORDER BY time_msc ASC
+        LIMIT ?4
+    "#;
+
+    let mut stmt = conn.prepare(sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![pair, from, to, limit], |row| {
+            Ok(Tick {
+                pair: row.get(0)?,
+                time: row.get(1)?,
+                time_msc: row.get(2)?,
+                bid: row.get(3)?,
+                ask: row.get(4)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut ticks = Vec::new();
+    for r in rows {
+        ticks.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(ticks)
+}
+
+/// pair の最新ティック。まだなければ None
+pub fn find_latest(state: &DbState, pair: &str) -> Result<Option<Tick>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let tick = conn.query_row(
+        "SELECT pair, time, time_msc, bid, ask
+        FROM ticks
+        WHERE pair = ?1
+        ORDER BY time_msc DESC
+        LIMIT 1",
+        params![pair],
+        |row| {
+            Ok(Tick {
+                pair: row.get(0)?,
+                time: row.get(1)?,
+                time_msc: row.get(2)?,
+                bid: row.get(3)?,
+                ask: row.get(4)?,
+            })
+        },
+    );
+
+    match tick {
+        Ok(t) => Ok(Some(t)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// time_msc 以前で最も新しいティック。from_msc より古ければ None
+pub fn find_quote_at(
+    state: &DbState,
+    pair: &str,
+    from_msc: i64,
+    time_msc: i64,
+) -> Result<Option<Tick>, String> {
+    find_quote(
+        state,
+        "WHERE pair = ?1 AND time_msc BETWEEN ?2 AND ?3 ORDER BY time_msc DESC",
+        params![pair, from_msc, time_msc],
+    )
+}
+
+/// [from_msc, to_msc] で最も古いティック
+pub fn find_first_quote(
+    state: &DbState,
+    pair: &str,
+    from_msc: i64,
+    to_msc: i64,
+) -> Result<Option<Tick>, String> {
+    find_quote(
+        state,
+        "WHERE pair = ?1 AND time_msc BETWEEN ?2 AND ?3 ORDER BY time_msc ASC",
+        params![pair, from_msc, to_msc],
+    )
+}
+
+fn find_quote(
+    state: &DbState,
+    condition: &str,
+    params: &[&dyn rusqlite::ToSql],
+) -> Result<Option<Tick>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let tick = conn.query_row(
+        &format!(
+            "SELECT pair, time, time_msc, bid, ask
+            FROM ticks
+            {}
+            LIMIT 1",
+            condition
+        ),
+        params,
+        |row| {
+            Ok(Tick {
+                pair: row.get(0)?,
+                time: row.get(1)?,
+                time_msc: row.get(2)?,
+                bid: row.get(3)?,
+                ask: row.get(4)?,
+            })
+        },
+    );
+
+    match tick {
+        Ok(t) => Ok(Some(t)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
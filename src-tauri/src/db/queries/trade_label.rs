@@ -1,17 +1,23 @@
-use rusqlite::{params, Result};
+use rusqlite::{params, Result, Transaction};
 
 use crate::db::DbState;
 
 pub fn insert_trade_label(state: &DbState, trade_id: i32, label_id: i32) -> Result<(), String> {
-    let state = state.conn.lock().map_err(|e| e.to_string())?;
-    state
-        .execute(
-            "INSERT OR IGNORE INTO trade_labels (
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    insert_trade_label_tx(&tx, trade_id, label_id)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// insert_trade_label のトランザクション版
+pub fn insert_trade_label_tx(tx: &Transaction, trade_id: i32, label_id: i32) -> Result<(), String> {
+    tx.execute(
+        "INSERT OR IGNORE INTO trade_labels (
         trade_id, label_id
         ) VALUES (?1, ?2)",
-            params![trade_id, label_id,],
-        )
-        .map_err(|e| e.to_string())?;
+        params![trade_id, label_id,],
+    )
+    .map_err(|e| e.to_string())?;
     Ok(())
 }
 
@@ -28,8 +34,14 @@ pub fn delete_trade_label(state: &DbState, trade_id: i32, label_id: i32) -> Resu
 }
 
 pub fn find_by_trade_id(db: &DbState, trade_id: i32) -> Result<Vec<i32>, String> {
-    let conn = db.conn.lock().unwrap();
-    let mut stmt = conn
+    let mut conn = db.conn.lock().unwrap();
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    find_by_trade_id_tx(&tx, trade_id)
+}
+
+/// find_by_trade_id のトランザクション版
+pub fn find_by_trade_id_tx(tx: &Transaction, trade_id: i32) -> Result<Vec<i32>, String> {
+    let mut stmt = tx
         .prepare("SELECT label_id FROM trade_labels WHERE trade_id = ?1")
         .map_err(|e| e.to_string())?;
 
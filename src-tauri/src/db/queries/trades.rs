@@ -1,13 +1,24 @@
-use rusqlite::{params, params_from_iter, Result, ToSql};
+use rusqlite::{params, params_from_iter, Result, ToSql, Transaction};
 
 use crate::db::DbState;
 use crate::models::db::trade::Trade;
 use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::service::trash::{TrashReason, TrashedTrade};
 use crate::utils::time_utils;
 
-pub fn insert_trade(state: &DbState, trade: Trade) -> Result<i64, String> {
-    let state = state.conn.lock().map_err(|e| e.to_string())?;
-    state.execute(
+/// 取引を追加し、(ID, 追加したか) を返す
+/// 同じ内容の取引があれば追加せず、その ID を返す
+pub fn insert_trade(state: &DbState, trade: Trade) -> Result<(i64, bool), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let result = insert_trade_tx(&tx, trade)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(result)
+}
+
+/// insert_trade のトランザクション版
+pub fn insert_trade_tx(tx: &Transaction, trade: Trade) -> Result<(i64, bool), String> {
+    let inserted = tx.execute(
         "INSERT OR IGNORE INTO trades (
         pair, side, lot, entry_rate, exit_rate, entry_time, exit_time, profit, profit_pips, swap, memo, account
         ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
@@ -27,7 +38,37 @@ pub fn insert_trade(state: &DbState, trade: Trade) -> Result<i64, String> {
         ],
     )
     .map_err(|e| e.to_string())?;
-    Ok(state.last_insert_rowid())
+    if inserted > 0 {
+        return Ok((tx.last_insert_rowid(), true));
+    }
+
+    // 無視されたときの last_insert_rowid は別の行を指すので探し直す
+    let id = tx
+        .query_row(
+            "SELECT id FROM trades
+            WHERE pair = ?1 AND side = ?2 AND lot = ?3
+              AND entry_rate = ?4 AND exit_rate = ?5
+              AND entry_time = ?6 AND exit_time = ?7
+              AND profit = ?8 AND profit_pips = ?9
+              AND swap IS ?10 AND account = ?11
+              AND split_from = 0 AND split_index = 0",
+            params![
+                trade.pair,
+                trade.side,
+                trade.lot,
+                trade.entry_rate,
+                trade.exit_rate,
+                trade.entry_time,
+                trade.exit_time,
+                trade.profit,
+                trade.profit_pips,
+                trade.swap,
+                trade.account,
+            ],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    Ok((id, false))
 }
 
 pub fn get_by_ids(state: &DbState, ids: Vec<i64>) -> Result<Vec<Trade>, String> {
@@ -40,7 +81,8 @@ pub fn get_by_ids(state: &DbState, ids: Vec<i64>) -> Result<Vec<Trade>, String>
 
     let sql = format!(
         "SELECT id, pair, side, lot, entry_rate, exit_rate,
-                entry_time, exit_time, profit, profit_pips, swap
+                entry_time, exit_time, profit, profit_pips, swap,
+                COALESCE(memo, ''), account
         FROM trades
         WHERE is_deleted = 0 AND id IN ({})",
         placeholders
@@ -62,7 +104,8 @@ pub fn get_by_ids(state: &DbState, ids: Vec<i64>) -> Result<Vec<Trade>, String>
                 profit: row.get(8)?,
                 profit_pips: row.get(9)?,
                 swap: row.get(10)?,
-                ..Default::default()
+                memo: row.get(11)?,
+                account: row.get(12)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -74,38 +117,54 @@ pub fn get_by_ids(state: &DbState, ids: Vec<i64>) -> Result<Vec<Trade>, String>
     Ok(trades)
 }
 
-pub fn find_similar_trades(state: &DbState, trade: Trade) -> Result<Vec<Trade>, String> {
+/// 同じ口座・通貨ペア・売買で、エントリーと決済の時刻が window_secs 以内の取引
+pub fn find_similar_trades(
+    state: &DbState,
+    trade: Trade,
+    window_secs: i64,
+) -> Result<Vec<Trade>, String> {
     let state = state.conn.lock().map_err(|e| e.to_string())?;
 
     let sql = r#"
         SELECT id, pair, side, lot, entry_rate, exit_rate,
                 entry_time, exit_time, profit, profit_pips, swap
         FROM trades
-        WHERE pair = ?
-          AND side = ?
-          AND ABS(entry_time - ?) <= 1
-          AND ABS(exit_time - ?) <= 1
+        WHERE pair = ?1
+          AND side = ?2
+          AND ABS(entry_time - ?3) <= ?5
+          AND ABS(exit_time - ?4) <= ?5
+          AND account = ?6
           AND is_deleted = 0
     "#;
 
     let mut stmt = state.prepare(sql).map_err(|e| e.to_string())?;
     let rows = stmt
-        .query_map([trade.pair, trade.side, trade.entry_time.to_string(), trade.exit_time.to_string()], |row| {
-            Ok(Trade {
-                id: row.get(0)?,
-                pair: row.get(1)?,
-                side: row.get(2)?,
-                lot: row.get(3)?,
-                entry_rate: row.get(4)?,
-                exit_rate: row.get(5)?,
-                entry_time: row.get(6)?,
-                exit_time: row.get(7)?,
-                profit: row.get(8)?,
-                profit_pips: row.get(9)?,
-                swap: row.get(10)?,
-                ..Default::default()
-            })
-        })
+        .query_map(
+            params![
+                trade.pair,
+                trade.side,
+                trade.entry_time,
+                trade.exit_time,
+                window_secs,
+                trade.account
+            ],
+            |row| {
+                Ok(Trade {
+                    id: row.get(0)?,
+                    pair: row.get(1)?,
+                    side: row.get(2)?,
+                    lot: row.get(3)?,
+                    entry_rate: row.get(4)?,
+                    exit_rate: row.get(5)?,
+                    entry_time: row.get(6)?,
+                    exit_time: row.get(7)?,
+                    profit: row.get(8)?,
+                    profit_pips: row.get(9)?,
+                    swap: row.get(10)?,
+                    ..Default::default()
+                })
+            },
+        )
         .map_err(|e| e.to_string())?;
 
     let mut trades = Vec::new();
@@ -116,12 +175,18 @@ pub fn find_similar_trades(state: &DbState, trade: Trade) -> Result<Vec<Trade>,
 }
 
 pub fn delete_by_ids(state: &DbState, ids: Vec<i64>) -> Result<(), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    delete_by_ids_tx(&tx, &ids)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// delete_by_ids のトランザクション版
+pub fn delete_by_ids_tx(tx: &Transaction, ids: &[i64]) -> Result<(), String> {
     if ids.is_empty() {
         return Ok(());
     }
 
-    let conn = state.conn.lock().map_err(|e| e.to_string())?;
-
     let placeholders = std::iter::repeat("?")
         .take(ids.len())
         .collect::<Vec<_>>()
@@ -136,18 +201,24 @@ pub fn delete_by_ids(state: &DbState, ids: Vec<i64>) -> Result<(), String> {
 
     let params: Vec<&dyn ToSql> = ids.iter().map(|id| id as &dyn ToSql).collect();
 
-    conn.execute(&sql, &params[..]).map_err(|e| e.to_string())?;
+    tx.execute(&sql, &params[..]).map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 pub fn update_merge_to(state: &DbState, ids: Vec<i64>, merge_to: i64) -> Result<(), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    update_merge_to_tx(&tx, &ids, merge_to)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// update_merge_to のトランザクション版
+pub fn update_merge_to_tx(tx: &Transaction, ids: &[i64], merge_to: i64) -> Result<(), String> {
     if ids.is_empty() {
         return Ok(());
     }
 
-    let conn = state.conn.lock().map_err(|e| e.to_string())?;
-
     let placeholders = std::iter::repeat("?")
         .take(ids.len())
         .collect::<Vec<_>>()
@@ -162,11 +233,11 @@ pub fn update_merge_to(state: &DbState, ids: Vec<i64>, merge_to: i64) -> Result<
 
     let mut params: Vec<&dyn rusqlite::ToSql> = Vec::with_capacity(1 + ids.len());
     params.push(&merge_to);
-    for id in &ids {
+    for id in ids {
         params.push(id);
     }
 
-    conn.execute(&sql, &params[..]).map_err(|e| e.to_string())?;
+    tx.execute(&sql, &params[..]).map_err(|e| e.to_string())?;
 
     Ok(())
 }
@@ -245,8 +316,14 @@ pub fn get_trades_by_label(state: &DbState, label_id: i32) -> Result<Vec<Trade>,
 }
 
 pub fn update_trade_memo_by_id(state: &DbState, trade: Trade) -> Result<(), String> {
-    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    update_trade_memo_by_id_tx(&tx, trade)?;
+    tx.commit().map_err(|e| e.to_string())
+}
 
+/// update_trade_memo_by_id のトランザクション版
+pub fn update_trade_memo_by_id_tx(tx: &Transaction, trade: Trade) -> Result<(), String> {
     // ベースSQL
     let mut sql = String::from(
         "UPDATE trades SET 
@@ -260,16 +337,87 @@ pub fn update_trade_memo_by_id(state: &DbState, trade: Trade) -> Result<(), Stri
     params.push(&trade.id);
 
     // 実行
-    conn.execute(&sql, params.as_slice())
+    tx.execute(&sql, params.as_slice())
         .map_err(|e| e.to_string())?;
 
     Ok(())
 }
 
 pub fn get_by_filter(state: &DbState, filter: TradeFilter) -> Result<Vec<Trade>, String> {
+    select_by_filter(state, &filter, " ORDER BY exit_time DESC", Vec::new())
+}
+
+/// フィルターに合うトレードを新しい順に offset 件目から最大 limit 件
+pub fn get_page_by_filter(
+    state: &DbState,
+    filter: &TradeFilter,
+    offset: i64,
+    limit: i64,
+) -> Result<Vec<Trade>, String> {
+    select_by_filter(
+        state,
+        filter,
+        " ORDER BY exit_time DESC, id DESC LIMIT ? OFFSET ?",
+        vec![Box::new(limit), Box::new(offset)],
+    )
+}
+
+/// フィルターに合うトレードの件数
+pub fn count_by_filter(state: &DbState, filter: &TradeFilter) -> Result<i64, String> {
     let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let (condition, params_vec) = filter_condition(filter);
+    let query = format!("SELECT COUNT(*) FROM trades WHERE is_deleted = 0{}", condition);
+
+    conn.query_row(&query, params_from_iter(params_vec.iter()), |row| row.get(0))
+        .map_err(|e| e.to_string())
+}
+
+fn select_by_filter(
+    state: &DbState,
+    filter: &TradeFilter,
+    suffix: &str,
+    extra_params: Vec<Box<dyn rusqlite::ToSql>>,
+) -> Result<Vec<Trade>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+
+    let (condition, mut params_vec) = filter_condition(filter);
+    let mut query = format!("SELECT * FROM trades WHERE is_deleted = 0{}", condition);
+    query.push_str(suffix);
+    params_vec.extend(extra_params);
+    println!("{}", query);
+
+    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params_from_iter(params_vec.iter()), |row| {
+            Ok(Trade {
+                id: row.get(0)?,
+                pair: row.get(1)?,
+                side: row.get(2)?,
+                lot: row.get(3)?,
+                entry_rate: row.get(4)?,
+                exit_rate: row.get(5)?,
+                entry_time: row.get(6)?,
+                exit_time: row.get(7)?,
+                profit: row.get(8)?,
+                profit_pips: row.get(9)?,
+                swap: row.get(10)?,
+                memo: row.get(11)?,
+                account: row.get(14)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut trades = Vec::new();
+    for r in rows {
+        trades.push(r.map_err(|e| e.to_string())?);
+    }
+
+    Ok(trades)
+}
 
-    let mut query = String::from("SELECT * FROM trades WHERE is_deleted = 0");
+/// フィルターを " AND ..." の条件とパラメータにする
+fn filter_condition(filter: &TradeFilter) -> (String, Vec<Box<dyn rusqlite::ToSql>>) {
+    let mut query = String::new();
     let mut params_vec: Vec<Box<dyn rusqlite::ToSql>> = Vec::new();
 
     if let Some(start_str) = &filter.start_date {
@@ -301,7 +449,7 @@ pub fn get_by_filter(state: &DbState, filter: TradeFilter) -> Result<Vec<Trade>,
     }
 
     // ラベルでのフィルター
-    if let Some(label_ids) = filter.label_ids {
+    if let Some(label_ids) = &filter.label_ids {
         if !label_ids.is_empty() {
             let placeholders = label_ids
                 .iter()
@@ -318,17 +466,405 @@ pub fn get_by_filter(state: &DbState, filter: TradeFilter) -> Result<Vec<Trade>,
             ));
 
             for id in label_ids {
-                params_vec.push(Box::new(id));
+                params_vec.push(Box::new(*id));
             }
         }
     }
 
-    query.push_str(" ORDER BY exit_time DESC");
-    println!("{}", query);
+    // MAE/MFE でのフィルター
+    let excursion_conditions = [
+        ("mae_pips >= ?", filter.min_mae_pips),
+        ("mae_pips <= ?", filter.max_mae_pips),
+        ("mfe_pips >= ?", filter.min_mfe_pips),
+        ("mfe_pips <= ?", filter.max_mfe_pips),
+    ];
+    for (condition, value) in excursion_conditions {
+        if let Some(v) = value {
+            query.push_str(&format!(
+                " AND id IN (SELECT trade_id FROM trade_excursions WHERE {})",
+                condition
+            ));
+            params_vec.push(Box::new(v));
+        }
+    }
 
-    let mut stmt = conn.prepare(&query).map_err(|e| e.to_string())?;
+    (query, params_vec)
+}
+
+/// id のトレード（削除済みを除く）
+pub fn find_by_id(state: &DbState, id: i64) -> Result<Option<Trade>, String> {
+    let trades = find_trades(state, "WHERE is_deleted = 0 AND id = ?1", params![id])?;
+    Ok(trades.into_iter().next())
+}
+
+/// merged_to に統合された元のトレード
+pub fn find_by_merged_to(state: &DbState, merged_to: i64) -> Result<Vec<Trade>, String> {
+    find_trades(state, "WHERE merged_to = ?1", params![merged_to])
+}
+
+/// 手入力のトレードを追加する。同じ内容のトレードがあればエラーにする
+pub fn insert_manual_trade(state: &DbState, trade: &Trade) -> Result<i64, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "INSERT INTO trades (
+        pair, side, lot, entry_rate, exit_rate, entry_time, exit_time, profit, profit_pips, swap, memo, account
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+        params![
+            trade.pair,
+            trade.side,
+            trade.lot,
+            trade.entry_rate,
+            trade.exit_rate,
+            trade.entry_time,
+            trade.exit_time,
+            trade.profit,
+            trade.profit_pips,
+            trade.swap,
+            trade.memo,
+            trade.account,
+        ],
+    )
+    .map_err(duplicate_error)?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// トレードの内容をすべて書き換える（削除済みは対象外）
+pub fn update_trade(state: &DbState, trade: &Trade) -> Result<(), String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE trades SET
+            pair = ?1, side = ?2, lot = ?3, entry_rate = ?4, exit_rate = ?5,
+            entry_time = ?6, exit_time = ?7, profit = ?8, profit_pips = ?9,
+            swap = ?10, memo = ?11, account = ?12
+         WHERE id = ?13 AND is_deleted = 0",
+        params![
+            trade.pair,
+            trade.side,
+            trade.lot,
+            trade.entry_rate,
+            trade.exit_rate,
+            trade.entry_time,
+            trade.exit_time,
+            trade.profit,
+            trade.profit_pips,
+            trade.swap,
+            trade.memo,
+            trade.account,
+            trade.id,
+        ],
+    )
+    .map_err(duplicate_error)?;
+    Ok(())
+}
+
+/// UNIQUE 制約の違反は画面に出せるメッセージにする
+fn duplicate_error(e: rusqlite::Error) -> String {
+    match e.sqlite_error() {
+        Some(err) if err.extended_code == rusqlite::ffi::SQLITE_CONSTRAINT_UNIQUE => {
+            "同じ内容のトレードがあります".to_string()
+        }
+        _ => e.to_string(),
+    }
+}
+
+/// 分割元のトレード ID（分割したトレードでなければ 0）
+pub fn get_split_from(state: &DbState, id: i64) -> Result<i64, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT split_from FROM trades WHERE id = ?1",
+        params![id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// キャンペーンに含まれるトレード（削除済みを除く）
+pub fn get_trades_by_campaign(state: &DbState, campaign_id: i64) -> Result<Vec<Trade>, String> {
+    find_trades(
+        state,
+        "WHERE is_deleted = 0
+           AND id IN (SELECT trade_id FROM campaign_trades WHERE campaign_id = ?1)",
+        params![campaign_id],
+    )
+}
+
+/// どのキャンペーンにも入っていないトレード（削除済みを除く）
+pub fn get_ungrouped_trades(state: &DbState) -> Result<Vec<Trade>, String> {
+    find_trades(
+        state,
+        "WHERE is_deleted = 0
+           AND id NOT IN (SELECT trade_id FROM campaign_trades)",
+        params![],
+    )
+}
+
+/// 同じ通貨ペアで [from, to) に保有期間が重なるトレード（削除済みと exclude_id を除く）
+/// 通貨ペアは "/" の有無を区別しない
+pub fn find_overlapping(
+    state: &DbState,
+    pair: &str,
+    from: i64,
+    to: i64,
+    exclude_id: i64,
+) -> Result<Vec<Trade>, String> {
+    find_trades(
+        state,
+        "WHERE is_deleted = 0
+           AND REPLACE(pair, '/', '') = ?1
+           AND entry_time < ?3 AND exit_time >= ?2
+           AND id != ?4",
+        params![pair, from, to, exclude_id],
+    )
+}
+
+/// 削除済みを含めてトレードがあるか
+pub fn exists(state: &DbState, id: i64) -> Result<bool, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM trades WHERE id = ?1)",
+        params![id],
+        |row| row.get(0),
+    )
+    .map_err(|e| e.to_string())
+}
+
+/// 削除済みを含む分割後のトレードの (id, merged_to, さらに分割したか)
+pub fn find_split_piece_lineage(
+    state: &DbState,
+    split_from: i64,
+) -> Result<Vec<(i64, Option<i64>, bool)>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.id, t.merged_to,
+                    EXISTS (SELECT 1 FROM trades p WHERE p.split_from = t.id)
+            FROM trades t
+            WHERE t.split_from = ?1",
+        )
+        .map_err(|e| e.to_string())?;
     let rows = stmt
-        .query_map(params_from_iter(params_vec.iter()), |row| {
+        .query_map(params![split_from], |row| {
+            Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut pieces = Vec::new();
+    for r in rows {
+        pieces.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(pieces)
+}
+
+/// 削除済みを含むすべてのトレードの (id, lot, merged_to, split_from)
+/// マージ・分割の前後で値を引き継ぐときに使う
+pub fn get_lineage(state: &DbState) -> Result<Vec<(i64, f64, Option<i64>, i64)>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let mut stmt = conn
+        .prepare("SELECT id, lot, merged_to, split_from FROM trades")
+        .map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)))
+        .map_err(|e| e.to_string())?;
+
+    let mut lineage = Vec::new();
+    for r in rows {
+        lineage.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(lineage)
+}
+
+/// 削除済みのトレード（新しく削除した順）
+pub fn get_trashed_trades(state: &DbState) -> Result<Vec<TrashedTrade>, String> {
+    find_trashed(state, "", params![])
+}
+
+pub fn find_trashed_by_id(state: &DbState, id: i64) -> Result<Option<TrashedTrade>, String> {
+    let trashed = find_trashed(state, "AND t.id = ?1", params![id])?;
+    Ok(trashed.into_iter().next())
+}
+
+fn find_trashed(
+    state: &DbState,
+    condition: &str,
+    params: &[&dyn ToSql],
+) -> Result<Vec<TrashedTrade>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT t.id, t.pair, t.side, t.lot, t.entry_rate, t.exit_rate,
+                t.entry_time, t.exit_time, t.profit, t.profit_pips, t.swap,
+                COALESCE(t.memo, ''), t.account, t.merged_to,
+                EXISTS (SELECT 1 FROM trades p WHERE p.split_from = t.id),
+                (SELECT MAX(a.changed_at) FROM audit_log a
+                  WHERE a.table_name = 'trades' AND a.trade_id = t.id
+                    AND json_extract(a.after, '$.is_deleted') = 1) AS deleted_at
+        FROM trades t
+        WHERE t.is_deleted = 1 {}
+        ORDER BY deleted_at DESC, t.exit_time DESC, t.id",
+        condition
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params, |row| {
+            let merged_to: Option<i64> = row.get(13)?;
+            let split: bool = row.get(14)?;
+            let reason = if merged_to.is_some() {
+                TrashReason::Merged
+            } else if split {
+                TrashReason::Split
+            } else {
+                TrashReason::Deleted
+            };
+
+            Ok(TrashedTrade {
+                trade: Trade {
+                    id: row.get(0)?,
+                    pair: row.get(1)?,
+                    side: row.get(2)?,
+                    lot: row.get(3)?,
+                    entry_rate: row.get(4)?,
+                    exit_rate: row.get(5)?,
+                    entry_time: row.get(6)?,
+                    exit_time: row.get(7)?,
+                    profit: row.get(8)?,
+                    profit_pips: row.get(9)?,
+                    swap: row.get(10)?,
+                    memo: row.get(11)?,
+                    account: row.get(12)?,
+                },
+                reason,
+                merged_to,
+                deleted_at: row.get(15)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    let mut trashed = Vec::new();
+    for r in rows {
+        trashed.push(r.map_err(|e| e.to_string())?);
+    }
+    Ok(trashed)
+}
+
+/// マージで削除扱いにしたトレードを戻す
+pub fn restore_by_ids(state: &DbState, ids: Vec<i64>) -> Result<(), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    restore_by_ids_tx(&tx, &ids)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// restore_by_ids のトランザクション版
+pub fn restore_by_ids_tx(tx: &Transaction, ids: &[i64]) -> Result<(), String> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+
+    let placeholders = std::iter::repeat("?")
+        .take(ids.len())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let sql = format!(
+        "UPDATE trades
+         SET is_deleted = 0, merged_to = NULL
+         WHERE id IN ({})",
+        placeholders
+    );
+
+    tx.execute(&sql, params_from_iter(ids.iter()))
+        .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// トレードとラベル・キャンペーンの紐付け、MAE/MFE を完全に削除する
+pub fn purge_by_id(state: &DbState, id: i64) -> Result<(), String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    purge_by_id_tx(&tx, id)?;
+    tx.commit().map_err(|e| e.to_string())
+}
+
+/// purge_by_id のトランザクション版
+pub fn purge_by_id_tx(tx: &Transaction, id: i64) -> Result<(), String> {
+    tx.execute("DELETE FROM trade_labels WHERE trade_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM campaign_trades WHERE trade_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM trade_excursions WHERE trade_id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM trades WHERE id = ?1", params![id])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// 分割したトレードを追加する。split_from, split_index で元のトレードと区別する
+pub fn insert_split_trade(
+    state: &DbState,
+    trade: Trade,
+    split_from: i64,
+    split_index: i64,
+) -> Result<i64, String> {
+    let mut conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let id = insert_split_trade_tx(&tx, trade, split_from, split_index)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(id)
+}
+
+/// insert_split_trade のトランザクション版
+pub fn insert_split_trade_tx(
+    tx: &Transaction,
+    trade: Trade,
+    split_from: i64,
+    split_index: i64,
+) -> Result<i64, String> {
+    tx.execute(
+        "INSERT INTO trades (
+        pair, side, lot, entry_rate, exit_rate, entry_time, exit_time, profit, profit_pips, swap, memo, account,
+        split_from, split_index
+        ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            trade.pair,
+            trade.side,
+            trade.lot,
+            trade.entry_rate,
+            trade.exit_rate,
+            trade.entry_time,
+            trade.exit_time,
+            trade.profit,
+            trade.profit_pips,
+            trade.swap,
+            trade.memo,
+            trade.account,
+            split_from,
+            split_index,
+        ],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(tx.last_insert_rowid())
+}
+
+fn find_trades(
+    state: &DbState,
+    condition: &str,
+    params: &[&dyn ToSql],
+) -> Result<Vec<Trade>, String> {
+    let conn = state.conn.lock().map_err(|e| e.to_string())?;
+    let sql = format!(
+        "SELECT id, pair, side, lot, entry_rate, exit_rate,
+                entry_time, exit_time, profit, profit_pips, swap,
+                COALESCE(memo, ''), account
+        FROM trades
+        {}
+        ORDER BY exit_time DESC, id",
+        condition
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params, |row| {
             Ok(Trade {
                 id: row.get(0)?,
                 pair: row.get(1)?,
@@ -342,7 +878,7 @@ pub fn get_by_filter(state: &DbState, filter: TradeFilter) -> Result<Vec<Trade>,
                 profit_pips: row.get(9)?,
                 swap: row.get(10)?,
                 memo: row.get(11)?,
-                account: row.get(14)?,
+                account: row.get(12)?,
             })
         })
         .map_err(|e| e.to_string())?;
@@ -351,6 +887,5 @@ pub fn get_by_filter(state: &DbState, filter: TradeFilter) -> Result<Vec<Trade>,
     for r in rows {
         trades.push(r.map_err(|e| e.to_string())?);
     }
-
     Ok(trades)
 }
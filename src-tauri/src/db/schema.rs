@@ -30,6 +30,7 @@ pub const TABLES: &[&str] = &[
     CREATE TABLE IF NOT EXISTS candles(
         id INTEGER PRIMARY KEY AUTOINCREMENT,
         pair TEXT NOT NULL,
+        -- timeframe TEXT NOT NULL DEFAULT 'M1',
         time INTEGER NOT NULL UNIQUE,  -- UNIXTIMEで管理
         open REAL NOT NULL,
         high REAL NOT NULL,
@@ -38,7 +39,7 @@ pub const TABLES: &[&str] = &[
         tickvol INTEGER,
         vol INTEGER,
         spread INTEGER,
-        UNIQUE(pair, time)
+        UNIQUE(pair, time)  -- 0.8.13 で UNIQUE(pair, timeframe, time) に変更
     )
     "#,
     r#"
@@ -77,6 +78,8 @@ pub const TABLES: &[&str] = &[
         -- is_deleted INTEGER DEFAULT 0,
         -- merged_to INTEGER,
         -- accunt TEXT,
+        -- split_from INTEGER NOT NULL DEFAULT 0,  -- 分割元の trades.id（0.8.14 で追加）
+        -- split_index INTEGER NOT NULL DEFAULT 0, -- 分割後の連番（0.8.14 で追加）
         UNIQUE(pair, side, lot , entry_time, exit_time, entry_rate, exit_rate, profit, profit_pips, swap)
     )
     "#,
@@ -100,6 +103,104 @@ pub const TABLES: &[&str] = &[
         memo TEXT
     )
     "#,
+    // 建て増し・分割決済をまとめて 1 つのポジションとして見る
+    r#"
+    CREATE TABLE IF NOT EXISTS campaigns(
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        pair TEXT NOT NULL,
+        side TEXT NOT NULL,           -- 買 or 売
+        memo TEXT,
+        auto INTEGER NOT NULL DEFAULT 0,  -- 自動でまとめたら 1
+        created_at INTEGER NOT NULL   -- UNIXTIMEで管理
+    )
+    "#,
+    r#"
+    CREATE TABLE IF NOT EXISTS campaign_trades(
+        campaign_id INTEGER NOT NULL,
+        trade_id INTEGER NOT NULL,
+        PRIMARY KEY(campaign_id, trade_id)
+    )
+    "#,
+    r#"
+    CREATE INDEX IF NOT EXISTS idx_campaign_trades_trade ON campaign_trades(trade_id);
+    "#,
+    // MT5 ブリッジから取り込んだ約定。ticket で重複を防ぐ
+    r#"
+    CREATE TABLE IF NOT EXISTS mt5_deals(
+        ticket INTEGER PRIMARY KEY,  -- MT5 の約定チケット
+        position_id INTEGER NOT NULL,
+        order_ticket INTEGER,
+        symbol TEXT NOT NULL,
+        type INTEGER NOT NULL,       -- 0: 買, 1: 売
+        entry INTEGER NOT NULL,      -- 0: 新規, 1: 決済, 2: ドテン, 3: 相殺決済
+        volume REAL NOT NULL,
+        price REAL NOT NULL,
+        profit REAL NOT NULL,
+        swap REAL NOT NULL,
+        commission REAL NOT NULL,
+        time INTEGER NOT NULL,       -- UNIXTIMEで管理
+        time_msc INTEGER NOT NULL,
+        magic INTEGER,
+        comment TEXT,
+        trade_id INTEGER             -- 決済の約定から作った trades.id
+    )
+    "#,
+    r#"
+    CREATE INDEX IF NOT EXISTS idx_mt5_deals_position ON mt5_deals(position_id);
+    "#,
+    // MT5 ブリッジから取得した銘柄の仕様
+    r#"
+    CREATE TABLE IF NOT EXISTS symbols(
+        name TEXT PRIMARY KEY,
+        description TEXT,
+        path TEXT,
+        currency_base TEXT,
+        currency_profit TEXT,
+        digits INTEGER NOT NULL,
+        point REAL NOT NULL,
+        contract_size REAL NOT NULL,
+        volume_min REAL,
+        volume_max REAL,
+        volume_step REAL,
+        swap_long REAL,
+        swap_short REAL,
+        swap_mode INTEGER,
+        trade_mode INTEGER,
+        sessions TEXT,               -- 取引時間帯の JSON 配列
+        updated_at INTEGER NOT NULL  -- UNIXTIMEで管理
+    )
+    "#,
+    // 取引ごとの MAE/MFE。ティック（なければ M1 のローソク足）から計算する
+    r#"
+    CREATE TABLE IF NOT EXISTS trade_excursions(
+        trade_id INTEGER PRIMARY KEY,
+        source TEXT NOT NULL,         -- tick or candle
+        mae_pips INTEGER NOT NULL,    -- 最大逆行（0 以上）
+        mfe_pips INTEGER NOT NULL,    -- 最大順行（0 以上）
+        mae_yen INTEGER NOT NULL,
+        mfe_yen INTEGER NOT NULL,
+        mae_secs INTEGER NOT NULL,    -- エントリーからの秒数
+        mfe_secs INTEGER NOT NULL,
+        computed_at INTEGER NOT NULL  -- UNIXTIMEで管理
+    )
+    "#,
+    // 取引・ラベル・日別メモの変更履歴。記録はトリガーで行う（0.8.15 で追加）
+    r#"
+    CREATE TABLE IF NOT EXISTS audit_log(
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        table_name TEXT NOT NULL,    -- trades, trade_labels, daily_memo
+        operation TEXT NOT NULL,     -- INSERT, UPDATE, DELETE
+        row_key TEXT NOT NULL,       -- 変更した行の id（daily_memo は date）
+        trade_id INTEGER,            -- 関係する trades.id
+        before TEXT,                 -- 変更前の行の JSON
+        after TEXT,                  -- 変更後の行の JSON
+        changed_at INTEGER NOT NULL  -- UNIXTIMEで管理
+    )
+    "#,
+    r#"
+    CREATE INDEX IF NOT EXISTS idx_audit_log_trade ON audit_log(trade_id);
+    "#,
     r#"
     CREATE TABLE IF NOT EXISTS meta (
         key TEXT PRIMARY KEY,
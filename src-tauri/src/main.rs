@@ -5,17 +5,22 @@
 
 mod commands;
 mod db;
+mod market_data;
 mod models;
-mod mt5_client;
 mod python_server;
 mod service;
 mod utils;
 
 use crate::db::DbState;
-use python_server::start_python_server;
-use std::sync::{Arc, Mutex};
-use tauri::WindowEvent;
-use std::process::Command;
+use crate::market_data::MarketDataState;
+use crate::models::settings::market_data::{MarketDataConfig, MARKET_DATA_CONFIG_KEY};
+use crate::models::settings::sync_scheduler::{SyncSchedulerConfig, SYNC_SCHEDULER_CONFIG_KEY};
+use crate::service::positions::PositionMonitorState;
+use crate::service::scheduler::SchedulerState;
+use crate::service::sync::SyncState;
+use crate::service::tick_stream::TickStreamState;
+use python_server::BridgeSupervisor;
+use tauri::{Manager, WindowEvent};
 
 #[tauri::command]
 fn quit_app() {
@@ -25,14 +30,19 @@ fn quit_app() {
 fn main() {
     let db = DbState::new().expect("Failed to init database");
 
-    // Python サーバー起動
-    let _python_server = match start_python_server() {
-        Ok(child) => Some(Arc::new(Mutex::new(child))),
-        Err(err) => {
-            eprintln!("Failed to start Python server: {}", err);
-            None
-        }
-    };
+    let market_config: MarketDataConfig =
+        service::settings::load(&db, MARKET_DATA_CONFIG_KEY).unwrap_or_else(|err| {
+            eprintln!("Failed to load market data config: {}", err);
+            MarketDataConfig::default()
+        });
+    let market = MarketDataState::new(market_config);
+
+    let scheduler_config: SyncSchedulerConfig =
+        service::settings::load(&db, SYNC_SCHEDULER_CONFIG_KEY).unwrap_or_else(|err| {
+            eprintln!("Failed to load sync scheduler config: {}", err);
+            SyncSchedulerConfig::default()
+        });
+    let scheduler = SchedulerState::new(scheduler_config);
 
     let app = tauri::Builder::default()
         .plugin(tauri_plugin_fs::init())
@@ -42,20 +52,32 @@ fn main() {
         .plugin(tauri_plugin_updater::Builder::new().build())
         .invoke_handler(tauri::generate_handler![quit_app])
         .manage(db)
-        .on_window_event(move |_window, event| {
+        .manage(market)
+        .manage(SyncState::default())
+        .manage(scheduler)
+        .manage(BridgeSupervisor::new())
+        .manage(PositionMonitorState::default())
+        .manage(TickStreamState::default())
+        .setup(|app| {
+            // Python サーバーの起動と死活監視
+            tauri::async_runtime::spawn(python_server::run_supervisor(app.handle().clone()));
+
+            // 価格データの定期同期
+            tauri::async_runtime::spawn(service::scheduler::run_scheduler(app.handle().clone()));
+
+            // 保有ポジションの評価（画面から有効にしたときだけ）
+            tauri::async_runtime::spawn(service::positions::run_position_monitor(app.handle().clone()));
+            Ok(())
+        })
+        .on_window_event(move |window, event| {
             if let WindowEvent::CloseRequested { api, .. } = event {
                 println!("Close requested: shutting down Python server...");
                 api.prevent_close(); // デフォルトの即終了を防ぐ
 
-                #[cfg(windows)]
-                {
-                    println!("Killing all mt5_server.exe ...");
-
-                    let _ = Command::new("taskkill")
-                        .args(&["/IM", "mt5_server.exe", "/F"])
-                        .status();
-
-                    println!("All mt5_server.exe processes killed.");
+                let app = window.app_handle();
+                if let Ok(config) = app.state::<MarketDataState>().config() {
+                    let supervisor = app.state::<BridgeSupervisor>();
+                    tauri::async_runtime::block_on(supervisor.shutdown(&config));
                 }
 
                 std::process::exit(0);
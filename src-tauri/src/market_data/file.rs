@@ -0,0 +1,220 @@
+use async_trait::async_trait;
+use std::path::{Path, PathBuf};
+
+use crate::market_data::MarketDataProvider;
+use crate::models::db::candle::Candle;
+use crate::models::db::tick::Tick;
+use crate::models::settings::market_data::MarketDataConfig;
+use crate::service::import::{read_mt5_candle_csv, read_mt5_tick_csv};
+
+/// data_dir に置いた MT5 エクスポート CSV から取得する
+/// ファイル名は sample/ と同じく {SYMBOL}_{TIMEFRAME}_*.csv / {SYMBOL}_tick_*.csv
+pub struct FileProvider {
+    data_dir: Option<PathBuf>,
+    ohlc_batch_size: usize,
+    tick_batch_size: usize,
+}
+
+impl FileProvider {
+    pub fn new(config: &MarketDataConfig) -> Self {
+        FileProvider {
+            data_dir: config.data_dir.as_ref().map(PathBuf::from),
+            ohlc_batch_size: config.ohlc_batch_size as usize,
+            tick_batch_size: config.tick_batch_size as usize,
+        }
+    }
+
+    fn data_dir(&self) -> Result<PathBuf, String> {
+        self.data_dir
+            .clone()
+            .ok_or("data_dir が設定されていません".into())
+    }
+}
+
+/// prefix に一致する CSV のパスを名前順で返す
+fn find_files(dir: &Path, prefix: &str) -> Result<Vec<String>, String> {
+    let mut paths = Vec::new();
+    for entry in std::fs::read_dir(dir).map_err(|e| e.to_string())? {
+        let path = entry.map_err(|e| e.to_string())?.path();
+        let name = match path.file_name().and_then(|n| n.to_str()) {
+            Some(n) => n,
+            None => continue,
+        };
+
+        // "USDJPY_M1" が "USDJPY_M15_..." に一致しないよう区切り文字まで見る
+        let matched = name
+            .strip_prefix(prefix)
+            .map(|rest| rest.starts_with('_') || rest.starts_with('.'))
+            .unwrap_or(false);
+        if matched && name.to_lowercase().ends_with(".csv") {
+            paths.push(path.to_string_lossy().to_string());
+        }
+    }
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// ファイルの読み込みは非同期ランタイムを止めないよう別スレッドで行う
+async fn run_blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, String> + Send + 'static,
+) -> Result<T, String> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+#[async_trait]
+impl MarketDataProvider for FileProvider {
+    /// ファイルごとに since より後を batch 件まで読み、まとめてから batch 件に絞る
+    async fn fetch_ohlc(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        since: i64,
+    ) -> Result<Vec<Candle>, String> {
+        let dir = self.data_dir()?;
+        let prefix = format!("{}_{}", symbol, timeframe);
+        let symbol = symbol.to_string();
+        let limit = self.ohlc_batch_size;
+
+        run_blocking(move || {
+            let mut candles = Vec::new();
+            for path in find_files(&dir, &prefix)? {
+                candles.extend(read_mt5_candle_csv(&path, &symbol, since, limit)?);
+            }
+
+            candles.sort_by_key(|c| c.time);
+            candles.dedup_by_key(|c| c.time);
+            candles.truncate(limit);
+            Ok(candles)
+        })
+        .await
+    }
+
+    async fn fetch_ticks(&self, symbol: &str, since: i64) -> Result<Vec<Tick>, String> {
+        let dir = self.data_dir()?;
+        let prefix = format!("{}_tick", symbol);
+        let symbol = symbol.to_string();
+        let limit = self.tick_batch_size;
+
+        run_blocking(move || {
+            let mut ticks = Vec::new();
+            for path in find_files(&dir, &prefix)? {
+                ticks.extend(read_mt5_tick_csv(&path, &symbol, since, limit)?);
+            }
+
+            ticks.sort_by_key(|t| t.time_msc);
+            ticks.dedup_by_key(|t| t.time_msc);
+            ticks.truncate(limit);
+            Ok(ticks)
+        })
+        .await
+    }
+
+    async fn ping(&self) -> Result<(), String> {
+        match self.data_dir.as_ref() {
+            Some(dir) if dir.is_dir() => Ok(()),
+            Some(dir) => Err(format!("data_dir が見つかりません: {}", dir.display())),
+            None => Err("data_dir が設定されていません".into()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::settings::market_data::ProviderKind;
+
+    // MT5 の「エクスポート」で出力した形式（タブ区切り・サーバー時刻）
+    const CANDLE_CSV: &str =
+        "<DATE>\t<TIME>\t<OPEN>\t<HIGH>\t<LOW>\t<CLOSE>\t<TICKVOL>\t<VOL>\t<SPREAD>\n\
+        2025.10.27\t00:00:00\t153.024\t153.132\t153.024\t153.131\t36\t0\t8\n\
+        2025.10.27\t00:01:00\t153.130\t153.136\t153.080\t153.080\t84\t0\t13\n\
+        2025.10.27\t00:02:00\t153.079\t153.080\t153.068\t153.069\t50\t0\t13\n";
+
+    // 片側だけ更新された行は BID/ASK が空。途中で切れた行も混ざる
+    const TICK_CSV: &str = "<DATE>\t<TIME>\t<BID>\t<ASK>\t<LAST>\t<VOLUME>\t<FLAGS>\n\
+        2025.10.27\t00:00:00.120\t153.024\t\t\t\t2\n\
+        2025.10.27\n\
+        2025.10.27\t00:00:00.250\t\t153.032\t\t\t4\n\
+        2025.10.27\t00:00:01.005\t153.026\t\t\t\t2\n";
+
+    fn fixture_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("fx_file_provider_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("USDJPY_M1_20251027.csv"), CANDLE_CSV).unwrap();
+        std::fs::write(dir.join("USDJPY_M15_20251027.csv"), CANDLE_CSV).unwrap();
+        std::fs::write(dir.join("USDJPY_tick_20251027.csv"), TICK_CSV).unwrap();
+        dir
+    }
+
+    fn provider(dir: &PathBuf, batch: u32) -> FileProvider {
+        FileProvider::new(&MarketDataConfig {
+            provider: ProviderKind::File,
+            data_dir: Some(dir.to_string_lossy().to_string()),
+            ohlc_batch_size: batch,
+            tick_batch_size: batch,
+            ..Default::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn reads_candles_after_since() {
+        let dir = fixture_dir("ohlc");
+        let p = provider(&dir, 100);
+
+        let candles = p.fetch_ohlc("USDJPY", "M1", 0).await.unwrap();
+        // M15 のファイルは混ざらない
+        assert_eq!(candles.len(), 3);
+        // 2025.10.27 00:00 はサーバー時刻（EET, UTC+2）
+        assert_eq!(candles[0].time, 1761516000);
+        assert_eq!(candles[0].open, 153.024);
+        assert_eq!(candles[1].tickvol, 84);
+        assert_eq!(candles[2].spread, 13);
+
+        let newer = p.fetch_ohlc("USDJPY", "M1", candles[0].time).await.unwrap();
+        assert_eq!(newer.len(), 2);
+
+        let limited = provider(&dir, 1)
+            .fetch_ohlc("USDJPY", "M1", 0)
+            .await
+            .unwrap();
+        assert_eq!(limited.len(), 1);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reads_ticks_carrying_over_missing_side() {
+        let dir = fixture_dir("tick");
+        let ticks = provider(&dir, 100).fetch_ticks("USDJPY", 0).await.unwrap();
+
+        // ASK が出るまでの行は捨て、以降は空の側を直前の値で埋める
+        assert_eq!(ticks.len(), 2);
+        assert_eq!(ticks[0].time_msc, 1761516000 * 1000 + 250);
+        assert_eq!((ticks[0].bid, ticks[0].ask), (153.024, 153.032));
+        assert_eq!(ticks[1].time, 1761516001);
+        assert_eq!((ticks[1].bid, ticks[1].ask), (153.026, 153.032));
+
+        let newer = provider(&dir, 100)
+            .fetch_ticks("USDJPY", ticks[1].time)
+            .await
+            .unwrap();
+        assert_eq!(newer.len(), 1);
+        assert_eq!(newer[0].ask, 153.032);
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn missing_data_dir_is_an_error() {
+        let p = FileProvider::new(&MarketDataConfig {
+            provider: ProviderKind::File,
+            ..Default::default()
+        });
+        assert!(p.fetch_ohlc("USDJPY", "M1", 0).await.is_err());
+        assert!(p.ping().await.is_err());
+    }
+}
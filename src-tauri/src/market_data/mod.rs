@@ -0,0 +1,84 @@
+use async_trait::async_trait;
+use std::sync::{Arc, RwLock};
+
+use crate::models::db::candle::Candle;
+use crate::models::db::deal::Deal;
+use crate::models::db::symbol::SymbolSpec;
+use crate::models::db::tick::Tick;
+use crate::models::service::position::OpenPosition;
+use crate::models::settings::market_data::{MarketDataConfig, ProviderKind};
+
+pub mod file;
+pub mod mt5;
+
+/// 価格データの取得元
+#[async_trait]
+pub trait MarketDataProvider: Send + Sync {
+    /// since（UNIX秒）より新しいローソク足を時刻昇順で取得する
+    async fn fetch_ohlc(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        since: i64,
+    ) -> Result<Vec<Candle>, String>;
+
+    /// since（UNIX秒）以降のティックを時刻昇順で取得する
+    async fn fetch_ticks(&self, symbol: &str, since: i64) -> Result<Vec<Tick>, String>;
+
+    /// since（UNIX秒）以降の約定履歴を取得する。ブリッジ以外は未対応
+    async fn fetch_deals(&self, _since: i64) -> Result<Vec<Deal>, String> {
+        Err("この取得元は取引履歴の取得に対応していません".into())
+    }
+
+    /// 保有中のポジションを取得する。ブリッジ以外は未対応
+    async fn fetch_positions(&self) -> Result<Vec<OpenPosition>, String> {
+        Err("この取得元は保有ポジションの取得に対応していません".into())
+    }
+
+    /// ブローカーが提供する銘柄の仕様を取得する。ブリッジ以外は未対応
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolSpec>, String> {
+        Err("この取得元は銘柄一覧の取得に対応していません".into())
+    }
+
+    /// 取得元に接続できるか確認する
+    async fn ping(&self) -> Result<(), String>;
+}
+
+pub fn create_provider(config: &MarketDataConfig) -> Arc<dyn MarketDataProvider> {
+    match config.provider {
+        ProviderKind::Mt5 => Arc::new(mt5::Mt5Provider::new(config)),
+        ProviderKind::File => Arc::new(file::FileProvider::new(config)),
+    }
+}
+
+/// Tauri で管理する価格データ取得の状態
+/// 設定を変更したらプロバイダーを作り直す
+pub struct MarketDataState {
+    inner: RwLock<(MarketDataConfig, Arc<dyn MarketDataProvider>)>,
+}
+
+impl MarketDataState {
+    pub fn new(config: MarketDataConfig) -> Self {
+        let provider = create_provider(&config);
+        Self {
+            inner: RwLock::new((config, provider)),
+        }
+    }
+
+    pub fn config(&self) -> Result<MarketDataConfig, String> {
+        let inner = self.inner.read().map_err(|e| e.to_string())?;
+        Ok(inner.0.clone())
+    }
+
+    pub fn provider(&self) -> Result<Arc<dyn MarketDataProvider>, String> {
+        let inner = self.inner.read().map_err(|e| e.to_string())?;
+        Ok(inner.1.clone())
+    }
+
+    pub fn reload(&self, config: MarketDataConfig) -> Result<(), String> {
+        let provider = create_provider(&config);
+        let mut inner = self.inner.write().map_err(|e| e.to_string())?;
+        *inner = (config, provider);
+        Ok(())
+    }
+}
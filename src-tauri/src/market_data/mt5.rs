@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+use crate::market_data::MarketDataProvider;
+use crate::models::db::candle::Candle;
+use crate::models::db::deal::Deal;
+use crate::models::db::symbol::SymbolSpec;
+use crate::models::db::tick::Tick;
+use crate::models::service::position::OpenPosition;
+use crate::models::settings::market_data::MarketDataConfig;
+
+// ティック取得はブリッジ側で数日分を読むので長めに待つ
+const REQUEST_TIMEOUT_SECS: u64 = 120;
+
+// 死活確認はすぐ返ってこなければ到達不能とみなす
+const PING_TIMEOUT_SECS: u64 = 5;
+
+/// MT5 ブリッジ (mt5_server) から HTTP で取得する
+pub struct Mt5Provider {
+    client: Client,
+    base_url: String,
+    ohlc_batch_size: u32,
+    tick_batch_size: u32,
+}
+
+impl Mt5Provider {
+    pub fn new(config: &MarketDataConfig) -> Self {
+        let client = Client::builder()
+            .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+            .build()
+            .unwrap_or_else(|_| Client::new());
+
+        Mt5Provider {
+            client,
+            base_url: config.base_url(),
+            ohlc_batch_size: config.ohlc_batch_size,
+            tick_batch_size: config.tick_batch_size,
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, String)],
+    ) -> Result<T, String> {
+        let resp = self
+            .client
+            .get(format!("{}{}", self.base_url, path))
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?;
+
+        resp.json::<T>().await.map_err(|e| e.to_string())
+    }
+}
+
+#[async_trait]
+impl MarketDataProvider for Mt5Provider {
+    async fn fetch_ohlc(
+        &self,
+        symbol: &str,
+        timeframe: &str,
+        since: i64,
+    ) -> Result<Vec<Candle>, String> {
+        self.get_json(
+            "/get_ohlc",
+            &[
+                ("symbol", symbol.to_string()),
+                ("timeframe", timeframe.to_string()),
+                ("since", since.to_string()),
+                ("batch_size", self.ohlc_batch_size.to_string()),
+            ],
+        )
+        .await
+    }
+
+    async fn fetch_ticks(&self, symbol: &str, since: i64) -> Result<Vec<Tick>, String> {
+        self.get_json(
+            "/get_ticks",
+            &[
+                ("symbol", symbol.to_string()),
+                ("since", since.to_string()),
+                ("batch_size", self.tick_batch_size.to_string()),
+            ],
+        )
+        .await
+    }
+
+    async fn fetch_deals(&self, since: i64) -> Result<Vec<Deal>, String> {
+        self.get_json("/get_deals", &[("since", since.to_string())]).await
+    }
+
+    async fn fetch_positions(&self) -> Result<Vec<OpenPosition>, String> {
+        self.get_json("/get_positions", &[]).await
+    }
+
+    async fn fetch_symbols(&self) -> Result<Vec<SymbolSpec>, String> {
+        self.get_json("/get_symbols", &[]).await
+    }
+
+    async fn ping(&self) -> Result<(), String> {
+        self.client
+            .get(format!("{}/health", self.base_url))
+            .timeout(Duration::from_secs(PING_TIMEOUT_SECS))
+            .send()
+            .await
+            .map_err(|e| e.to_string())?
+            .error_for_status()
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
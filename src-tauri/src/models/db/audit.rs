@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+/// audit_log の 1 行。before / after は変更前後の行の JSON
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct AuditEntry {
+    pub id: i64,
+    pub table_name: String,
+    pub operation: String, // INSERT, UPDATE, DELETE
+    pub row_key: String,
+    pub trade_id: Option<i64>,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub changed_at: i64,
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// 複数の取引を 1 つのポジションとして見るためのまとまり
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Campaign {
+    pub id: Option<i64>,
+    pub name: String,
+    pub pair: String,
+    pub side: String, // 買 or 売
+    pub memo: String,
+    pub auto: bool,      // 自動でまとめたもの
+    pub created_at: i64, // UNIXTIMEで管理
+}
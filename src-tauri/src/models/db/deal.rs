@@ -0,0 +1,43 @@
+use serde::{Deserialize, Serialize};
+
+// MT5 の DEAL_TYPE
+pub const DEAL_TYPE_BUY: i32 = 0;
+pub const DEAL_TYPE_SELL: i32 = 1;
+
+// MT5 の DEAL_ENTRY
+pub const DEAL_ENTRY_IN: i32 = 0;
+pub const DEAL_ENTRY_OUT: i32 = 1;
+pub const DEAL_ENTRY_INOUT: i32 = 2;
+pub const DEAL_ENTRY_OUT_BY: i32 = 3;
+
+/// MT5 の約定（ブリッジの /get_deals と mt5_deals テーブル）
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct Deal {
+    pub ticket: i64,
+    pub position_id: i64,
+    pub order: i64,
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub deal_type: i32,
+    pub entry: i32,
+    pub volume: f64,
+    pub price: f64,
+    pub profit: f64,
+    pub swap: f64,
+    pub commission: f64,
+    pub time: i64, // UNIXTIME（UTC）
+    pub time_msc: i64,
+    pub magic: i64,
+    pub comment: String,
+    #[serde(default)]
+    pub trade_id: Option<i64>, // 決済の約定から作った trades.id
+}
+
+impl Deal {
+    pub fn is_exit(&self) -> bool {
+        matches!(
+            self.entry,
+            DEAL_ENTRY_OUT | DEAL_ENTRY_INOUT | DEAL_ENTRY_OUT_BY
+        )
+    }
+}
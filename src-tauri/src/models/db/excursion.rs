@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+// MAE/MFE を計算したデータ
+pub const EXCURSION_SOURCE_TICK: &str = "tick";
+pub const EXCURSION_SOURCE_CANDLE: &str = "candle";
+
+/// 保有中の最大逆行（MAE）と最大順行（MFE）
+/// どちらも 0 以上の値で、pips は profit_pips と同じ単位
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+pub struct TradeExcursion {
+    pub trade_id: i64,
+    pub source: String, // tick or candle
+    pub mae_pips: i32,
+    pub mfe_pips: i32,
+    pub mae_yen: i32,
+    pub mfe_yen: i32,
+    pub mae_secs: i64, // エントリーから MAE までの秒数
+    pub mfe_secs: i64, // エントリーから MFE までの秒数
+    pub computed_at: i64,
+}
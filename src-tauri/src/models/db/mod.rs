@@ -1,5 +1,10 @@
+pub mod audit;
+pub mod campaign;
 pub mod candle;
+pub mod deal;
+pub mod excursion;
 pub mod label;
 pub mod record;
+pub mod symbol;
 pub mod trade;
 pub mod tick;
@@ -11,4 +11,6 @@ pub struct Record {
     pub profit: Option<i32>,
     pub swap: Option<i32>,
     pub order_time: i64,
+    pub ticket: Option<i64>,      // MT5 の約定チケット（CSV 取り込みでは None）
+    pub position_id: Option<i64>, // MT5 のポジションID。あれば建玉の突き合わせに使う
 }
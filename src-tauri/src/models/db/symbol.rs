@@ -0,0 +1,33 @@
+use serde::{Deserialize, Serialize};
+
+/// 取引時間帯（UTC）。weekday は 0: 月曜 〜 6: 日曜
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct TradingSession {
+    pub weekday: u8,
+    pub open: String,  // "HH:MM"
+    pub close: String, // "HH:MM"（24:00 は翌日 0 時）
+}
+
+/// ブローカーが提供する銘柄の仕様（ブリッジの /get_symbols と symbols テーブル）
+#[derive(Debug, Serialize, Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct SymbolSpec {
+    pub name: String,
+    pub description: String,
+    pub path: String,            // MT5 の銘柄ツリー上の場所 (Forex\Majors\USDJPY など)
+    pub currency_base: String,
+    pub currency_profit: String, // 損益が計算される通貨
+    pub digits: i32,
+    pub point: f64,
+    pub contract_size: f64,      // 1 ロットあたりの数量
+    pub volume_min: f64,
+    pub volume_max: f64,
+    pub volume_step: f64,
+    pub swap_long: f64,
+    pub swap_short: f64,
+    pub swap_mode: i32,          // MT5 の SYMBOL_SWAP_MODE
+    pub trade_mode: i32,         // MT5 の SYMBOL_TRADE_MODE（0 は取引不可）
+    pub sessions: Vec<TradingSession>,
+    pub updated_at: i64,         // カタログを取得した時刻（UNIX秒）
+    pub selected: bool,          // 同期対象に選ばれているか（保存はしない）
+}
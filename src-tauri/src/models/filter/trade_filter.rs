@@ -1,10 +1,14 @@
 use serde::Deserialize;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Default)]
 pub struct TradeFilter {
     pub start_date: Option<String>,
     pub end_date: Option<String>,
     pub min_holding_time: Option<i64>,
     pub max_holding_time: Option<i64>,
     pub label_ids: Option<Vec<i64>>,
+    pub min_mae_pips: Option<i64>, // MAE/MFE は計算済みのトレードだけが対象
+    pub max_mae_pips: Option<i64>,
+    pub min_mfe_pips: Option<i64>,
+    pub max_mfe_pips: Option<i64>,
 }
@@ -1,3 +1,4 @@
 pub mod db;
 pub mod filter;
 pub mod service;
+pub mod settings;
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BridgeState {
+    #[default]
+    Starting,     // 起動直後で応答待ち
+    Running,      // /health に応答あり
+    Unreachable,  // 応答なし
+    Crashed,      // プロセスが終了し再起動待ち
+    NotInstalled, // mt5_server が見つからない（外部で起動したブリッジのみ監視）
+    Disabled,     // MT5 ブリッジを使わない設定
+    Stopped,      // アプリ終了などで停止済み
+}
+
+/// MT5 ブリッジ (mt5_server) の状態
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct BridgeStatus {
+    pub state: BridgeState,
+    pub managed: bool,                // アプリがプロセスを起動・監視しているか
+    pub pid: Option<u32>,             // 起動中のプロセスID
+    pub restarts: u32,                // 起動してからの再起動回数
+    pub last_exit: Option<String>,    // 直近のプロセス終了理由
+    pub last_error: Option<String>,   // 直近の起動エラー
+    pub next_restart_at: Option<i64>, // 次の再起動予定（UNIX秒）
+    pub last_healthy_at: Option<i64>, // 最後に応答があった時刻（UNIX秒）
+    pub log_path: Option<String>,     // ブリッジのログファイル
+}
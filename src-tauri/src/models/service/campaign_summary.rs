@@ -0,0 +1,19 @@
+use crate::models::db::campaign::Campaign;
+use crate::models::service::trade_summary::TradeSummary;
+use serde::{Deserialize, Serialize};
+
+/// キャンペーン全体での集計
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct CampaignSummary {
+    pub campaign: Campaign,
+    pub summary: TradeSummary, // 含まれる取引とその統計
+
+    pub total_lot: f64,      // 建てたロットの合計
+    pub max_exposure: f64,   // 同時に保有したロットの最大
+    pub avg_entry_rate: f64, // ロットで加重平均した建値
+    pub avg_exit_rate: f64,  // ロットで加重平均した決済レート
+    pub profit: i32,         // 損益の合計
+    pub swap: i32,           // スワップの合計
+    pub first_entry: i64,
+    pub last_exit: i64,
+}
@@ -0,0 +1,124 @@
+use crate::models::db::candle::Candle;
+use crate::models::db::tick::Tick;
+
+// チャンク種別
+pub const KIND_CANDLE: u8 = 1;
+pub const KIND_TICK: u8 = 2;
+
+// ヘッダーサイズ（以降の f64 配列が 8byte 境界に揃うようにする）
+pub const HEADER_SIZE: usize = 8;
+
+/// ストリーミング用のバイナリチャンク（リトルエンディアン）
+///
+/// ヘッダー: [kind: u8][is_last: u8][reserved: u16][count: u32]
+/// 本体: 列ごとの f64 配列を count 件ずつ連結
+///   ローソク足: time / open / high / low / close
+///   ティック:   time_msc / bid / ask
+/// 時刻も f64 で格納し、フロントエンドで Float64Array としてそのまま読めるようにする
+fn encode(kind: u8, is_last: bool, count: usize, columns: &[&Vec<f64>]) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(HEADER_SIZE + count * 8 * columns.len());
+    buf.push(kind);
+    buf.push(is_last as u8);
+    buf.extend_from_slice(&0u16.to_le_bytes());
+    buf.extend_from_slice(&(count as u32).to_le_bytes());
+
+    for column in columns {
+        for v in column.iter() {
+            buf.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    buf
+}
+
+#[derive(Debug, Default)]
+pub struct CandleColumns {
+    pub time: Vec<f64>,
+    pub open: Vec<f64>,
+    pub high: Vec<f64>,
+    pub low: Vec<f64>,
+    pub close: Vec<f64>,
+}
+
+impl CandleColumns {
+    pub fn with_capacity(capacity: usize) -> Self {
+        CandleColumns {
+            time: Vec::with_capacity(capacity),
+            open: Vec::with_capacity(capacity),
+            high: Vec::with_capacity(capacity),
+            low: Vec::with_capacity(capacity),
+            close: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, candle: &Candle) {
+        self.time.push(candle.time as f64);
+        self.open.push(candle.open);
+        self.high.push(candle.high);
+        self.low.push(candle.low);
+        self.close.push(candle.close);
+    }
+
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.time.clear();
+        self.open.clear();
+        self.high.clear();
+        self.low.clear();
+        self.close.clear();
+    }
+
+    pub fn to_bytes(&self, is_last: bool) -> Vec<u8> {
+        encode(
+            KIND_CANDLE,
+            is_last,
+            self.len(),
+            &[&self.time, &self.open, &self.high, &self.low, &self.close],
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct TickColumns {
+    pub time_msc: Vec<f64>,
+    pub bid: Vec<f64>,
+    pub ask: Vec<f64>,
+}
+
+impl TickColumns {
+    pub fn with_capacity(capacity: usize) -> Self {
+        TickColumns {
+            time_msc: Vec::with_capacity(capacity),
+            bid: Vec::with_capacity(capacity),
+            ask: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn push(&mut self, tick: &Tick) {
+        self.time_msc.push(tick.time_msc as f64);
+        self.bid.push(tick.bid);
+        self.ask.push(tick.ask);
+    }
+
+    pub fn len(&self) -> usize {
+        self.time_msc.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.time_msc.clear();
+        self.bid.clear();
+        self.ask.clear();
+    }
+
+    pub fn to_bytes(&self, is_last: bool) -> Vec<u8> {
+        encode(
+            KIND_TICK,
+            is_last,
+            self.len(),
+            &[&self.time_msc, &self.bid, &self.ask],
+        )
+    }
+}
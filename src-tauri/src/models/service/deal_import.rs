@@ -0,0 +1,12 @@
+use crate::models::service::import_summary::AutoMerge;
+use serde::{Deserialize, Serialize};
+
+/// MT5 からの取引履歴取り込み結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct DealImportSummary {
+    pub fetched: usize,              // ブリッジから受け取った約定
+    pub new_deals: usize,            // そのうち未取り込みだった約定
+    pub trades: usize,               // 追加した取引
+    pub auto_merges: Vec<AutoMerge>, // 自動マージした取引
+    pub errors: Vec<String>,         // 組み立てられなかったポジション
+}
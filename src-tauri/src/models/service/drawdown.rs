@@ -0,0 +1,115 @@
+/// 決済順に損益を積み上げ、最高値からの下落を追う（成績指標と損益曲線で共通）
+/// 割合（%）は開始残高があるときだけ求める。損益だけの曲線では 0 からの割合に意味がない
+#[derive(Debug, Clone, Default)]
+pub struct Drawdown {
+    pub equity: f64,
+    pub peak: f64,
+    peak_time: i64,
+    has_balance: bool,
+    pub max_drawdown: f64,
+    pub max_drawdown_pct: Option<f64>,
+    pub max_drawdown_duration: i64, // 最高値を下回っていた最長期間（秒）
+}
+
+/// 1 点ごとの下落
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DrawdownPoint {
+    pub drawdown: f64,             // 最高値からの下落（0 以上）
+    pub drawdown_pct: Option<f64>, // 開始残高があるときの最高値に対する割合（%）
+    pub duration: i64,             // 最高値を下回っている間の、最高値からの経過秒数
+}
+
+impl Drawdown {
+    /// start_time を開始残高（なければ 0）の時刻とする
+    pub fn new(starting_balance: Option<f64>, start_time: i64) -> Self {
+        let balance = starting_balance.unwrap_or(0.0);
+        Self {
+            equity: balance,
+            peak: balance,
+            peak_time: start_time,
+            has_balance: starting_balance.is_some(),
+            ..Default::default()
+        }
+    }
+
+    /// time に pnl を積み上げ、その時点の下落を返す
+    pub fn step(&mut self, time: i64, pnl: f64) -> DrawdownPoint {
+        let was_below = self.equity < self.peak;
+        self.equity += pnl;
+
+        if self.equity >= self.peak {
+            // 高値を取り戻した時点までを下落の期間とする
+            if was_below {
+                self.max_drawdown_duration = self.max_drawdown_duration.max(time - self.peak_time);
+            }
+            self.peak = self.equity;
+            self.peak_time = time;
+            return DrawdownPoint::default();
+        }
+
+        let drawdown = self.peak - self.equity;
+        let drawdown_pct = if self.has_balance && self.peak > 0.0 {
+            Some(drawdown / self.peak * 100.0)
+        } else {
+            None
+        };
+        let duration = time - self.peak_time;
+
+        self.max_drawdown = self.max_drawdown.max(drawdown);
+        if let Some(pct) = drawdown_pct {
+            self.max_drawdown_pct = Some(self.max_drawdown_pct.map_or(pct, |m| m.max(pct)));
+        }
+        self.max_drawdown_duration = self.max_drawdown_duration.max(duration);
+
+        DrawdownPoint {
+            drawdown,
+            drawdown_pct,
+            duration,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rising_equity_has_no_drawdown() {
+        let mut dd = Drawdown::new(None, 0);
+        for (time, pnl) in [(100, 10.0), (5000, 20.0), (90000, 5.0)] {
+            assert_eq!(dd.step(time, pnl), DrawdownPoint::default());
+        }
+        assert_eq!(dd.max_drawdown, 0.0);
+        assert_eq!(dd.max_drawdown_duration, 0);
+    }
+
+    #[test]
+    fn duration_runs_until_the_peak_is_regained() {
+        let mut dd = Drawdown::new(None, 0);
+        dd.step(100, 200.0);
+        let p = dd.step(200, -50.0);
+        assert_eq!((p.drawdown, p.duration), (50.0, 100));
+        let p = dd.step(300, -30.0);
+        assert_eq!((p.drawdown, p.duration), (80.0, 200));
+        dd.step(400, 100.0);
+        // 最高値を更新した後の上昇は期間に入らない
+        dd.step(10_000, 10.0);
+
+        assert_eq!(dd.max_drawdown, 80.0);
+        assert_eq!(dd.max_drawdown_duration, 300);
+        assert_eq!(dd.equity, 230.0);
+    }
+
+    #[test]
+    fn percentage_needs_a_starting_balance() {
+        let mut without = Drawdown::new(None, 0);
+        without.step(100, 200.0);
+        assert_eq!(without.step(200, -100.0).drawdown_pct, None);
+        assert_eq!(without.max_drawdown_pct, None);
+
+        let mut with = Drawdown::new(Some(1000.0), 0);
+        with.step(100, 200.0);
+        assert_eq!(with.step(200, -300.0).drawdown_pct, Some(25.0));
+        assert_eq!(with.max_drawdown_pct, Some(25.0));
+    }
+}
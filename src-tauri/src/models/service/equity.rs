@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// 曲線の 1 点をトレードごとにするか取引日ごとにするか
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum EquityGranularity {
+    #[default]
+    Trade,
+    Day,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct EquityCurveOptions {
+    pub granularity: EquityGranularity,
+    pub include_swap: bool,            // 取引の損益はスワップ込み。false ならスワップを除く
+    pub include_fees: bool,            // MT5 の約定の手数料
+    pub starting_balance: Option<f64>, // 口座残高から始めるとき
+}
+
+// 省略したときは集計（TradeSummary や日別）と同じくスワップ込みにする
+impl Default for EquityCurveOptions {
+    fn default() -> Self {
+        Self {
+            granularity: EquityGranularity::default(),
+            include_swap: true,
+            include_fees: false,
+            starting_balance: None,
+        }
+    }
+}
+
+/// 損益曲線の 1 点
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EquityPoint {
+    pub time: i64,                 // 決済時刻（取引日ごとはその日最後の決済）
+    pub date: String,              // 取引日 (YYYY-MM-DD)
+    pub trade_id: Option<i64>,     // トレードごとのとき
+    pub trade_count: i32,          // この点に含まれるトレード数
+    pub pnl: f64,                  // この点の損益
+    pub equity: f64,               // 累積（開始残高を含む）
+    pub peak: f64,                 // それまでの最高値
+    pub drawdown: f64,             // 最高値からの下落（0 以上）
+    pub drawdown_pct: Option<f64>, // 最高値に対する割合（%）。開始残高がないか最高値が 0 以下なら None
+    pub drawdown_duration: i64,    // 最高値を下回っている間の、最高値をつけてからの経過秒数
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EquityCurve {
+    pub options: EquityCurveOptions,
+    pub start_time: i64, // 最初のトレードのエントリー（開始残高の点）
+    pub points: Vec<EquityPoint>,
+    pub final_equity: f64,
+    pub max_drawdown: f64,
+    pub max_drawdown_pct: Option<f64>,
+    pub max_drawdown_duration: i64,
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+
+/// MAE/MFE の一括計算の結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExcursionComputeSummary {
+    pub trades: usize,       // 対象にした取引
+    pub from_ticks: usize,   // ティックから計算した取引
+    pub from_candles: usize, // ローソク足から計算した取引
+    pub no_data: usize,      // 保有期間の相場データがなかった取引
+}
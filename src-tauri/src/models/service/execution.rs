@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum FillKind {
+    #[default]
+    Entry,
+    Exit,
+}
+
+/// 1 回の約定と、その時点の気配との比較
+/// slippage_pips は気配より不利なら正、有利なら負
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FillQuality {
+    pub trade_id: i64,
+    pub kind: FillKind,
+    pub time: i64,
+    pub rate: f64,
+    pub quote: f64, // 買いは ask、売りは bid
+    pub quote_time_msc: i64,
+    pub slippage_pips: i32,
+    pub spread_pips: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExecutionStats {
+    pub count: i32,
+    pub avg_slippage_pips: f64,
+    pub avg_spread_pips: f64,
+    pub worst_slippage_pips: i32,
+    pub improved_rate: f64, // 気配より有利に約定した割合
+}
+
+/// 時間帯・ロット・口座ごとの集計
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExecutionGroup {
+    pub name: String,
+    pub entry: ExecutionStats,
+    pub exit: ExecutionStats,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ExecutionQuality {
+    pub overall: ExecutionGroup,
+    pub by_hour: Vec<ExecutionGroup>, // JST のエントリー・決済時刻の時
+    pub by_lot: Vec<ExecutionGroup>,
+    pub by_account: Vec<ExecutionGroup>,
+    pub fills: Vec<FillQuality>,
+    pub no_quote: usize, // ティックがなく比べられなかった約定
+}
@@ -0,0 +1,29 @@
+use serde::{Deserialize, Serialize};
+
+/// ヒートマップの 1 マス
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct HeatmapCell {
+    pub count: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_rate: f64, // 勝ち / 回数
+    pub profit: i32,
+    pub profit_pips: i32,
+    pub avg_profit: f64,
+    pub avg_profit_pips: f64,
+}
+
+/// エントリー時刻の時間帯・曜日・市場ごとの成績
+/// 市場は重なる時間帯があるため、市場ごとの合計は全体と一致しない
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TradeHeatmap {
+    pub hours: Vec<String>,    // JST の時
+    pub weekdays: Vec<String>, // JST の曜日（月〜日）
+    pub sessions: Vec<String>, // 東京・ロンドン・ニューヨーク・その他
+    pub total: HeatmapCell,
+    pub by_hour: Vec<HeatmapCell>,
+    pub by_weekday: Vec<HeatmapCell>,
+    pub by_session: Vec<HeatmapCell>,
+    pub weekday_hour: Vec<Vec<HeatmapCell>>,    // [曜日][時]
+    pub weekday_session: Vec<Vec<HeatmapCell>>, // [曜日][市場]
+}
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+/// 取り込み時に自動でまとめた取引
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct AutoMerge {
+    pub merged_id: i64,       // まとめた後の取引
+    pub source_ids: Vec<i64>, // まとめた取引
+    pub pair: String,
+    pub side: String,
+    pub lot: f64,
+    pub entry_time: i64,
+    pub exit_time: i64,
+}
+
+/// CSV からの取引取り込み結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ImportSummary {
+    pub trades: usize,               // 追加した取引
+    pub auto_merges: Vec<AutoMerge>, // 自動マージした取引
+}
@@ -1,4 +1,5 @@
 use crate::models::db::trade::Trade;
+use crate::models::service::trade_metrics::TradeMetrics;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
 
@@ -15,4 +16,8 @@ pub struct LabelSummary {
     pub loss_total: i32,         // 負けトレード総額
     pub total_holding_time: i64, // 合計保有時間
     pub trades: Vec<Trade>,      // トレード一覧
+
+    // 成績指標（TradeSummary と同じ計算）
+    #[serde(flatten)]
+    pub metrics: TradeMetrics,
 }
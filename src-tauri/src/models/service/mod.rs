@@ -1,3 +1,23 @@
+pub mod bridge_status;
+pub mod campaign_summary;
+pub mod columnar;
 pub mod daily_summary;
+pub mod deal_import;
+pub mod drawdown;
+pub mod equity;
+pub mod excursion_compute;
+pub mod execution;
+pub mod heatmap;
+pub mod import_summary;
 pub mod label_summary;
+pub mod period_summary;
+pub mod position;
+pub mod post_exit;
+pub mod scheduler_status;
+pub mod sync;
+pub mod tick_stream;
+pub mod trade_context;
+pub mod trade_metrics;
+pub mod trade_page;
 pub mod trade_summary;
+pub mod trash;
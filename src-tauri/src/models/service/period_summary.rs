@@ -0,0 +1,76 @@
+use crate::models::service::trade_summary::TradeSummary;
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+/// 集計する期間の単位（取引日で区切る）
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SummaryPeriod {
+    Day,
+    #[default]
+    Week, // 月曜始まり
+    Month,
+    Quarter,
+    Year,
+}
+
+impl SummaryPeriod {
+    /// 取引日が属する期間の初日
+    pub fn start_of(&self, date: NaiveDate) -> NaiveDate {
+        let first_of_month = |month: u32| NaiveDate::from_ymd_opt(date.year(), month, 1);
+        match self {
+            SummaryPeriod::Day => Some(date),
+            SummaryPeriod::Week => {
+                Some(date - Duration::days(date.weekday().num_days_from_monday() as i64))
+            }
+            SummaryPeriod::Month => first_of_month(date.month()),
+            SummaryPeriod::Quarter => first_of_month((date.month() - 1) / 3 * 3 + 1),
+            SummaryPeriod::Year => first_of_month(1),
+        }
+        .unwrap_or(date)
+    }
+
+    /// 期間の最終日
+    pub fn end_of(&self, start: NaiveDate) -> NaiveDate {
+        let next = match self {
+            SummaryPeriod::Day => start.succ_opt(),
+            SummaryPeriod::Week => Some(start + Duration::days(7)),
+            SummaryPeriod::Month => start.checked_add_months(chrono::Months::new(1)),
+            SummaryPeriod::Quarter => start.checked_add_months(chrono::Months::new(3)),
+            SummaryPeriod::Year => start.checked_add_months(chrono::Months::new(12)),
+        };
+        next.and_then(|d| d.pred_opt()).unwrap_or(start)
+    }
+
+    /// "2025-10-06", "2025-W41", "2025-10", "2025-Q4", "2025" のような表示名
+    pub fn label(&self, start: NaiveDate) -> String {
+        match self {
+            SummaryPeriod::Day => start.format("%Y-%m-%d").to_string(),
+            SummaryPeriod::Week => start.format("%G-W%V").to_string(),
+            SummaryPeriod::Month => start.format("%Y-%m").to_string(),
+            SummaryPeriod::Quarter => format!("{}-Q{}", start.year(), (start.month() - 1) / 3 + 1),
+            SummaryPeriod::Year => start.format("%Y").to_string(),
+        }
+    }
+}
+
+/// 前の期間からの変化
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PeriodChange {
+    pub previous: String, // 比べた期間
+    pub profit: i32,
+    pub profit_pips: i32,
+    pub profit_pct: Option<f64>, // 前の期間の損益の絶対値に対する割合（%）
+    pub count: i32,
+    pub win_rate: f64, // 勝率の差
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct PeriodSummary {
+    pub period: SummaryPeriod,
+    pub label: String,
+    pub start: NaiveDate, // 期間の最初と最後の取引日
+    pub end: NaiveDate,
+    pub summary: TradeSummary,
+    pub change: Option<PeriodChange>, // トレードのある直前の期間と比べる
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 保有ポジションの取得元
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum PositionSource {
+    Bridge, // ブリッジの /get_positions
+    Ledger, // 取り込み済みの約定から復元
+}
+
+/// 保有中のポジション（ブリッジの /get_positions）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct OpenPosition {
+    pub ticket: i64,
+    pub symbol: String,
+    #[serde(rename = "type")]
+    pub position_type: i32, // 0: 買, 1: 売
+    pub volume: f64,
+    pub price_open: f64,
+    pub time: i64, // UNIXTIME（UTC）
+    #[serde(default)]
+    pub swap: f64,
+}
+
+/// 最新ティックで評価したポジション
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LivePosition {
+    pub ticket: i64,
+    pub pair: String,
+    pub side: String, // 買 or 売
+    pub lot: f64,     // trades と同じ 1 万通貨単位
+    pub entry_rate: f64,
+    pub entry_time: i64,
+    pub current_rate: Option<f64>, // 買いは bid、売りは ask。ティックがなければ None
+    pub price_time: Option<i64>,   // 評価に使ったティックの時刻（UNIX秒）
+    pub unrealized: Option<f64>,   // 含み損益（決済通貨建て、スワップ除く）
+    pub currency: String,          // 含み損益の通貨。分からなければ空
+    pub pips: Option<i32>,         // 銘柄の価格単位で数える　ドル円: 1円→1000
+    pub swap: f64,
+    pub holding_secs: i64,
+}
+
+/// 保有ポジションの一覧と合計
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PositionSnapshot {
+    pub source: PositionSource,
+    pub positions: Vec<LivePosition>,
+    pub total_unrealized: BTreeMap<String, f64>, // 通貨ごとの含み損益の合計
+    pub updated_at: i64,
+    pub error: Option<String>, // ブリッジから取れず約定から復元したときの理由
+}
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// 決済後 window_secs の間の値動き。pips は取引の方向で見た値
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PostExitMove {
+    pub window_secs: i64,
+    pub favorable_pips: i32, // 決済後に伸びた最大（0 以上）
+    pub adverse_pips: i32,   // 決済後に戻った最大（0 以上）
+    pub net_pips: i32,       // 期間の終わりの値と決済レートの差
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PostExitTrade {
+    pub trade_id: i64,
+    pub moves: Vec<PostExitMove>, // ローソク足がある期間のみ
+}
+
+/// 期間ごとの平均
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PostExitStats {
+    pub window_secs: i64,
+    pub count: i32,
+    pub avg_favorable_pips: f64,
+    pub avg_adverse_pips: f64,
+    pub avg_net_pips: f64,
+    pub continued_rate: f64, // 決済後も同じ方向に進んだ割合（早すぎた決済）
+}
+
+/// ラベル・保有時間ごとの集計
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PostExitGroup {
+    pub name: String,
+    pub label_id: Option<i32>,
+    pub min_holding_secs: Option<i64>,
+    pub max_holding_secs: Option<i64>, // この値未満
+    pub stats: Vec<PostExitStats>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct PostExitAnalysis {
+    pub windows_secs: Vec<i64>,
+    pub overall: Vec<PostExitStats>,
+    pub by_label: Vec<PostExitGroup>,
+    pub by_holding: Vec<PostExitGroup>,
+    pub trades: Vec<PostExitTrade>,
+}
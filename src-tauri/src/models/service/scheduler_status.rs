@@ -0,0 +1,17 @@
+use serde::{Deserialize, Serialize};
+
+/// バックグラウンド同期の状態
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SchedulerStatus {
+    pub enabled: bool,
+    pub syncing: bool,                  // 定期同期を実行中か
+    pub paused_until: Option<i64>,      // 一時停止の期限（UNIX秒）
+    pub bridge_reachable: Option<bool>, // 直近の死活確認の結果
+    pub last_run_at: Option<i64>,       // 直近の実行開始時刻（UNIX秒）
+    pub last_finished_at: Option<i64>,  // 直近の実行終了時刻（UNIX秒）
+    pub last_candles: usize,            // 直近の実行で追加したローソク足
+    pub last_ticks: usize,              // 直近の実行で追加したティック
+    pub last_errors: Vec<String>,       // 直近の実行で発生したエラー
+    pub next_run_at: Option<i64>,       // 次回の実行予定（UNIX秒）
+    pub runs: u64,                      // 起動してからの実行回数
+}
@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncKind {
+    Ohlc,
+    Tick,
+}
+
+impl SyncKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncKind::Ohlc => "ohlc",
+            SyncKind::Tick => "tick",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SyncStatus {
+    Running,
+    Retrying,
+    Done,
+    Cancelled,
+    Failed,
+}
+
+/// 同期の進捗（銘柄・足種ごと）
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SyncProgress {
+    pub kind: SyncKind,
+    pub symbol: String,
+    pub timeframe: Option<String>, // ティックは None
+    pub status: SyncStatus,
+    pub fetched: usize,            // 追加した件数
+    pub latest_time: i64,          // 保存済みの最新時刻（UNIX秒）
+    pub attempt: u32,              // リトライ回数
+    pub message: Option<String>,   // エラー内容など
+}
+
+/// 最後に同期が成功したときの記録（meta に保存）
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LastSync {
+    pub kind: Option<SyncKind>,
+    pub symbol: String,
+    pub timeframe: Option<String>,
+    pub synced_at: i64,   // 同期完了時刻（UNIX秒）
+    pub latest_time: i64, // その時点の最新データ時刻（UNIX秒）
+    pub fetched: usize,   // その回に追加した件数
+}
+
+/// 同期ジョブ全体の結果
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SyncSummary {
+    pub candles: usize,
+    pub ticks: usize,
+    pub cancelled: bool,
+    pub errors: Vec<String>,
+}
@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+
+/// リアルタイムティック受信の状態
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TickStreamStatus {
+    pub running: bool,
+    pub connected: bool,              // ブリッジと接続中か
+    pub symbols: Vec<String>,         // 受信中の通貨ペア
+    pub received: usize,              // 開始してから受け取ったティック
+    pub written: usize,               // そのうち ticks に追加した件数
+    pub reconnects: u32,              // 再接続の回数
+    pub last_tick_time: Option<i64>,  // 最後に受け取ったティックの時刻（UNIX秒）
+    pub last_error: Option<String>,   // 直近の切断理由
+    pub next_retry_at: Option<i64>,   // 再接続の予定（UNIX秒）
+}
@@ -0,0 +1,32 @@
+use crate::models::db::candle::Candle;
+use crate::models::db::trade::Trade;
+use crate::models::service::execution::FillKind;
+use serde::{Deserialize, Serialize};
+
+/// チャートに置くエントリー・決済の印
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TradeMarker {
+    pub trade_id: i64,
+    pub kind: FillKind,
+    pub side: String,
+    pub time: i64,
+    pub bar_time: i64, // 印を置く足の開始時刻
+    pub rate: f64,
+}
+
+/// トレード前後のチャートを見返すための情報
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TradeContext {
+    pub trade: Trade,
+    pub interval: i64,
+    pub from: i64, // 表示範囲 [from, to)
+    pub to: i64,
+    pub candles: Vec<Candle>,
+    pub truncated: bool, // 足が多すぎて表示範囲の途中までしか読み込めなかった
+    pub markers: Vec<TradeMarker>,
+    pub overlapping: Vec<Trade>, // 表示範囲に保有期間が重なる同じ通貨ペアのトレード
+    pub business_date: String,   // 決済日の取引日 (YYYY-MM-DD)
+    pub business_day_start: i64,
+    pub business_day_end: i64,
+    pub daily_memo: String,
+}
@@ -0,0 +1,269 @@
+use crate::models::db::trade::Trade;
+use crate::models::service::drawdown::Drawdown;
+use crate::utils::time_utils::get_business_date_from_unix;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// 損益の並びから求める成績指標（集計の種類によらず同じ計算）
+/// 分母が 0 で求められない比率は None
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TradeMetrics {
+    pub profit_factor: Option<f64>, // 勝ちトレード総額 / 負けトレード総額
+    pub expectancy: f64,            // 1 トレードあたりの期待損益
+    pub expectancy_pips: f64,
+    pub payoff_ratio: Option<f64>, // 平均利益 / 平均損失
+    pub largest_win: i32,          // 最大の勝ち
+    pub largest_loss: i32,         // 最大の負け（負の値）
+    pub max_consecutive_wins: i32,
+    pub max_consecutive_losses: i32,
+
+    // 決済順に損益を積み上げた曲線から
+    // 開始残高がないので、割合は積み上げた損益の最高値に対して求める（最高値が 0 以下なら None）
+    pub max_drawdown: i32,             // 最大ドローダウン（額）
+    pub max_drawdown_pct: Option<f64>, // 最大ドローダウン（最高値に対する %）
+    pub max_drawdown_duration: i64,    // 高値を下回っていた最長期間（秒）
+    pub recovery_factor: Option<f64>,  // 総利益 / 最大ドローダウン
+
+    // 取引日ごとの損益から（年率換算しない）
+    pub trading_days: i32,
+    pub sharpe_ratio: Option<f64>,
+    pub sortino_ratio: Option<f64>,
+
+    pub std_dev_profit: f64, // 1 トレードの損益の標準偏差
+    pub std_dev_pips: f64,
+}
+
+impl TradeMetrics {
+    pub fn from_trades(trades: &[Trade]) -> Self {
+        if trades.is_empty() {
+            return TradeMetrics::default();
+        }
+
+        let mut sorted: Vec<&Trade> = trades.iter().collect();
+        sorted.sort_by_key(|t| (t.exit_time, t.id));
+
+        let count = trades.len() as f64;
+        let profit: i64 = trades.iter().map(|t| t.profit as i64).sum();
+        let profit_pips: i64 = trades.iter().map(|t| t.profit_pips as i64).sum();
+        let wins: Vec<f64> = trades
+            .iter()
+            .filter(|t| t.profit > 0)
+            .map(|t| t.profit as f64)
+            .collect();
+        let losses: Vec<f64> = trades
+            .iter()
+            .filter(|t| t.profit < 0)
+            .map(|t| t.profit as f64)
+            .collect();
+        let win_total: f64 = wins.iter().sum();
+        let loss_total: f64 = -losses.iter().sum::<f64>();
+
+        let profit_factor = if loss_total > 0.0 {
+            Some(win_total / loss_total)
+        } else {
+            None
+        };
+        let payoff_ratio = if !wins.is_empty() && !losses.is_empty() {
+            Some((win_total / wins.len() as f64) / (loss_total / losses.len() as f64))
+        } else {
+            None
+        };
+
+        // 連勝・連敗（引き分けで途切れる）
+        let mut max_consecutive_wins = 0;
+        let mut max_consecutive_losses = 0;
+        let mut streak = 0i32;
+        for t in &sorted {
+            streak = match t.profit {
+                p if p > 0 => streak.max(0) + 1,
+                p if p < 0 => streak.min(0) - 1,
+                _ => 0,
+            };
+            max_consecutive_wins = max_consecutive_wins.max(streak);
+            max_consecutive_losses = max_consecutive_losses.max(-streak);
+        }
+
+        // ドローダウン（最初のエントリー時点の 0 を最初の高値とする）
+        let mut drawdown = Drawdown::new(None, sorted[0].entry_time);
+        let mut max_drawdown_pct: Option<f64> = None;
+        for t in &sorted {
+            let point = drawdown.step(t.exit_time, t.profit as f64);
+            if point.drawdown > 0.0 && drawdown.peak > 0.0 {
+                let pct = point.drawdown / drawdown.peak * 100.0;
+                max_drawdown_pct = Some(max_drawdown_pct.map_or(pct, |m| m.max(pct)));
+            }
+        }
+        let max_drawdown = drawdown.max_drawdown.round() as i64;
+        let recovery_factor = if max_drawdown > 0 {
+            Some(profit as f64 / max_drawdown as f64)
+        } else {
+            None
+        };
+
+        // 取引日ごとの損益
+        let mut daily: BTreeMap<chrono::NaiveDate, f64> = BTreeMap::new();
+        for t in trades {
+            *daily
+                .entry(get_business_date_from_unix(t.exit_time))
+                .or_insert(0.0) += t.profit as f64;
+        }
+        let returns: Vec<f64> = daily.into_values().collect();
+        let (sharpe_ratio, sortino_ratio) = daily_ratios(&returns);
+
+        TradeMetrics {
+            profit_factor,
+            expectancy: profit as f64 / count,
+            expectancy_pips: profit_pips as f64 / count,
+            payoff_ratio,
+            largest_win: trades.iter().map(|t| t.profit).max().unwrap_or(0).max(0),
+            largest_loss: trades.iter().map(|t| t.profit).min().unwrap_or(0).min(0),
+            max_consecutive_wins,
+            max_consecutive_losses,
+            max_drawdown: max_drawdown as i32,
+            max_drawdown_pct,
+            max_drawdown_duration: drawdown.max_drawdown_duration,
+            recovery_factor,
+            trading_days: returns.len() as i32,
+            sharpe_ratio,
+            sortino_ratio,
+            std_dev_profit: std_dev(&trades.iter().map(|t| t.profit as f64).collect::<Vec<_>>()),
+            std_dev_pips: std_dev(
+                &trades
+                    .iter()
+                    .map(|t| t.profit_pips as f64)
+                    .collect::<Vec<_>>(),
+            ),
+        }
+    }
+}
+
+/// 標本標準偏差（2 件未満は 0）
+fn std_dev(values: &[f64]) -> f64 {
+    if values.len() < 2 {
+        return 0.0;
+    }
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let var = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    var.sqrt()
+}
+
+/// 日次損益のシャープレシオとソルティノレシオ
+/// ソルティノの下方偏差は 0 を下回った分だけで求める
+fn daily_ratios(returns: &[f64]) -> (Option<f64>, Option<f64>) {
+    if returns.len() < 2 {
+        return (None, None);
+    }
+    let n = returns.len() as f64;
+    let mean = returns.iter().sum::<f64>() / n;
+
+    let sd = std_dev(returns);
+    let sharpe = if sd > 0.0 { Some(mean / sd) } else { None };
+
+    let downside = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / n).sqrt();
+    let sortino = if downside > 0.0 {
+        Some(mean / downside)
+    } else {
+        None
+    };
+
+    (sharpe, sortino)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2023-11-15 の取引日。1 日ずつずらすと別の取引日になる
+    const DAY: i64 = 86400;
+    const BASE: i64 = 1_700_000_000;
+
+    fn trade(id: i32, exit_time: i64, profit: i32) -> Trade {
+        Trade {
+            id: Some(id),
+            entry_time: exit_time - 60,
+            exit_time,
+            profit,
+            profit_pips: profit / 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_trades_give_default_metrics() {
+        let m = TradeMetrics::from_trades(&[]);
+        assert_eq!(m.profit_factor, None);
+        assert_eq!(m.max_drawdown, 0);
+        assert_eq!(m.max_drawdown_pct, None);
+        assert_eq!(m.trading_days, 0);
+    }
+
+    #[test]
+    fn profit_factor_and_payoff() {
+        let m = TradeMetrics::from_trades(&[
+            trade(1, BASE, 100),
+            trade(2, BASE + 10, 200),
+            trade(3, BASE + 20, -100),
+        ]);
+        assert_eq!(m.profit_factor, Some(3.0));
+        assert_eq!(m.payoff_ratio, Some(1.5));
+        assert!((m.expectancy - 200.0 / 3.0).abs() < 1e-9);
+        assert_eq!((m.largest_win, m.largest_loss), (200, -100));
+
+        // 負けがなければ求めない
+        let m = TradeMetrics::from_trades(&[trade(1, BASE, 100)]);
+        assert_eq!(m.profit_factor, None);
+        assert_eq!(m.payoff_ratio, None);
+    }
+
+    #[test]
+    fn streaks_follow_exit_order_and_break_on_even() {
+        // 並びは決済順に直してから数える
+        let profits = [100, 50, 0, -10, -20, -30, 40];
+        let mut trades: Vec<Trade> = profits
+            .iter()
+            .enumerate()
+            .map(|(i, p)| trade(i as i32 + 1, BASE + i as i64 * 10, *p))
+            .collect();
+        trades.reverse();
+
+        let m = TradeMetrics::from_trades(&trades);
+        assert_eq!(m.max_consecutive_wins, 2);
+        assert_eq!(m.max_consecutive_losses, 3);
+    }
+
+    #[test]
+    fn drawdown_duration_counts_only_time_below_the_peak() {
+        let m = TradeMetrics::from_trades(&[
+            trade(1, BASE, 100),
+            // 間が空いても高値を更新し続けていればドローダウンではない
+            trade(2, BASE + 10 * DAY, 100),
+            trade(3, BASE + 10 * DAY + 100, -50),
+            trade(4, BASE + 10 * DAY + 200, -30),
+            trade(5, BASE + 10 * DAY + 300, 100),
+        ]);
+        assert_eq!(m.max_drawdown, 80);
+        // 最高値 200 から 80 下げた
+        assert_eq!(m.max_drawdown_pct, Some(40.0));
+        assert_eq!(m.max_drawdown_duration, 300);
+        assert_eq!(m.recovery_factor, Some(220.0 / 80.0));
+    }
+
+    #[test]
+    fn sharpe_and_sortino_use_daily_profit() {
+        let m = TradeMetrics::from_trades(&[
+            trade(1, BASE, 60),
+            trade(2, BASE + 10, 40),
+            trade(3, BASE + DAY, -50),
+            trade(4, BASE + 2 * DAY, 200),
+        ]);
+        assert_eq!(m.trading_days, 3);
+        // 日次損益 [100, -50, 200]
+        assert!((m.sharpe_ratio.unwrap() - 0.662_266_178_5).abs() < 1e-9);
+        assert!((m.sortino_ratio.unwrap() - 2.886_751_345_9).abs() < 1e-9);
+
+        // 1 日だけでは求めない
+        let m = TradeMetrics::from_trades(&[trade(1, BASE, 100), trade(2, BASE + 10, -50)]);
+        assert_eq!((m.sharpe_ratio, m.sortino_ratio), (None, None));
+    }
+}
@@ -0,0 +1,11 @@
+use crate::models::db::trade::Trade;
+use serde::{Deserialize, Serialize};
+
+/// フィルターに合うトレードの 1 ページ分
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TradePage {
+    pub trades: Vec<Trade>,
+    pub total: i64, // フィルターに合うトレードの総数
+    pub offset: i64,
+    pub limit: i64,
+}
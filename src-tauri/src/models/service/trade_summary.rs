@@ -1,6 +1,9 @@
+use crate::models::db::excursion::TradeExcursion;
 use crate::models::db::trade::Trade;
+use crate::models::service::trade_metrics::TradeMetrics;
 use chrono::NaiveDate;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct TradeSummary {
@@ -23,6 +26,17 @@ pub struct TradeSummary {
     pub avg_holding_time: f64,        // 平均保有時間
     pub avg_holding_time_wins: f64,   // 勝ちトレード平均保有時間
     pub avg_holding_time_losses: f64, // 勝ちトレード平均保有時間
+
+    // 成績指標
+    #[serde(flatten)]
+    pub metrics: TradeMetrics,
+
+    // MAE/MFE（計算済みのトレードのみ）
+    pub excursion_count: i32, // MAE/MFE を計算済みのトレード回数
+    pub avg_mae_pips: f64,    // 平均最大逆行
+    pub avg_mfe_pips: f64,    // 平均最大順行
+    pub avg_mae_yen: f64,
+    pub avg_mfe_yen: f64,
 }
 
 impl TradeSummary {
@@ -105,6 +119,8 @@ impl TradeSummary {
             0.0
         };
 
+        let metrics = TradeMetrics::from_trades(&trades);
+
         TradeSummary {
             trades,
             profit,
@@ -123,6 +139,34 @@ impl TradeSummary {
             avg_holding_time,
             avg_holding_time_wins,
             avg_holding_time_losses,
+            metrics,
+            ..Default::default()
         }
     }
+
+    /// トレード一覧を外した集計だけを返す
+    pub fn without_trades(mut self) -> Self {
+        self.trades = Vec::new();
+        self
+    }
+
+    /// 集計対象のトレードの MAE/MFE を平均する
+    pub fn apply_excursions(&mut self, excursions: &HashMap<i64, TradeExcursion>) {
+        let targets: Vec<&TradeExcursion> = self
+            .trades
+            .iter()
+            .filter_map(|t| t.id)
+            .filter_map(|id| excursions.get(&(id as i64)))
+            .collect();
+        if targets.is_empty() {
+            return;
+        }
+
+        let n = targets.len() as f64;
+        self.excursion_count = targets.len() as i32;
+        self.avg_mae_pips = targets.iter().map(|e| e.mae_pips as f64).sum::<f64>() / n;
+        self.avg_mfe_pips = targets.iter().map(|e| e.mfe_pips as f64).sum::<f64>() / n;
+        self.avg_mae_yen = targets.iter().map(|e| e.mae_yen as f64).sum::<f64>() / n;
+        self.avg_mfe_yen = targets.iter().map(|e| e.mfe_yen as f64).sum::<f64>() / n;
+    }
 }
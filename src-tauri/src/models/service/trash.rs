@@ -0,0 +1,21 @@
+use crate::models::db::trade::Trade;
+use serde::{Deserialize, Serialize};
+
+/// 削除済みになった理由
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum TrashReason {
+    Merged, // マージされた元のトレード
+    Split,  // 分割された元のトレード
+    #[default]
+    Deleted, // 削除したトレード
+}
+
+/// ゴミ箱に入っているトレード
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct TrashedTrade {
+    pub trade: Trade,
+    pub reason: TrashReason,
+    pub merged_to: Option<i64>,  // マージ後のトレード
+    pub deleted_at: Option<i64>, // 履歴から分かる削除時刻
+}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+// 設定を保存する meta のキー
+pub const ANALYSIS_CONFIG_KEY: &str = "analysis_config";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AnalysisConfig {
+    pub post_exit_windows_secs: Vec<i64>, // 決済後の値動きを見る期間（秒）
+    pub holding_buckets_secs: Vec<i64>,   // 保有時間で分ける境界（秒）
+    pub lot_buckets: Vec<f64>,            // ロットで分ける境界
+    pub max_quote_age_secs: i64,          // 約定と比べるティックの古さの上限
+}
+
+impl Default for AnalysisConfig {
+    fn default() -> Self {
+        AnalysisConfig {
+            post_exit_windows_secs: vec![300, 1800, 14400],
+            holding_buckets_secs: vec![300, 3600, 14400, 86400],
+            lot_buckets: vec![0.5, 1.0, 3.0, 10.0],
+            max_quote_age_secs: 60,
+        }
+    }
+}
+
+impl AnalysisConfig {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.post_exit_windows_secs.is_empty()
+            || self.post_exit_windows_secs.iter().any(|w| *w <= 0)
+        {
+            return Err("決済後の値動きを見る期間は 1 つ以上、0 秒より長くしてください".into());
+        }
+        // 負の値だとティックを探す範囲が空になり、すべて「ティックなし」になる
+        if self.max_quote_age_secs < 0 {
+            return Err("ティックの古さの上限は 0 秒以上にしてください".into());
+        }
+        Ok(())
+    }
+}
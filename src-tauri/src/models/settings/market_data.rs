@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+
+// 設定を保存する meta のキー
+pub const MARKET_DATA_CONFIG_KEY: &str = "market_data_config";
+
+// シンボル未指定時に使う通貨ペア
+pub const DEFAULT_SYMBOL: &str = "USDJPY";
+
+// チャート表示の元になる足種
+pub const BASE_TIMEFRAME: &str = "M1";
+
+// 1 取引の分析で読み込む BASE_TIMEFRAME の足の上限（M1 で約 2 か月）
+pub const MAX_BASE_CANDLES: i64 = 100_000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProviderKind {
+    Mt5,  // MT5 ブリッジ (mt5_server) から HTTP で取得
+    File, // data_dir の MT5 エクスポート CSV から取得（MT5 のない環境での開発・テスト用）
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct MarketDataConfig {
+    pub provider: ProviderKind,
+    pub host: String,
+    pub port: u16,
+    pub symbols: Vec<String>,     // 同期対象の通貨ペア
+    pub timeframes: Vec<String>,  // 同期対象の足種 (M1, M5, H1 ...)
+    pub ohlc_batch_size: u32,     // 1リクエストで取得するローソク足の本数
+    pub tick_batch_size: u32,     // 1リクエストで取得するティック数
+    pub data_dir: Option<String>, // File プロバイダーの読み込み先
+}
+
+impl Default for MarketDataConfig {
+    fn default() -> Self {
+        MarketDataConfig {
+            provider: ProviderKind::Mt5,
+            host: "127.0.0.1".to_string(),
+            port: 5000,
+            symbols: vec![DEFAULT_SYMBOL.to_string()],
+            timeframes: vec![BASE_TIMEFRAME.to_string()],
+            ohlc_batch_size: 1000,
+            tick_batch_size: 5000,
+            data_dir: None,
+        }
+    }
+}
+
+impl MarketDataConfig {
+    pub fn base_url(&self) -> String {
+        format!("http://{}:{}", self.host, self.port)
+    }
+
+    /// 保存前に設定を確かめる
+    pub fn validate(&self) -> Result<(), String> {
+        match self.provider {
+            ProviderKind::Mt5 => {
+                if self.host.trim().is_empty() {
+                    return Err("MT5 ブリッジのホストを指定してください".into());
+                }
+                if self.port == 0 {
+                    return Err("MT5 ブリッジのポートを指定してください".into());
+                }
+            }
+            ProviderKind::File => {
+                let dir = self
+                    .data_dir
+                    .as_deref()
+                    .map(str::trim)
+                    .filter(|d| !d.is_empty())
+                    .ok_or("data_dir を指定してください")?;
+                if !std::path::Path::new(dir).is_dir() {
+                    return Err(format!("data_dir が見つかりません: {}", dir));
+                }
+            }
+        }
+
+        if self.symbols.is_empty() || self.symbols.iter().any(|s| s.trim().is_empty()) {
+            return Err("同期対象の通貨ペアを指定してください".into());
+        }
+        if self.timeframes.is_empty() || self.timeframes.iter().any(|t| t.trim().is_empty()) {
+            return Err("同期対象の足種を指定してください".into());
+        }
+        if self.ohlc_batch_size == 0 || self.tick_batch_size == 0 {
+            return Err("取得件数は 1 以上にしてください".into());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+// 設定を保存する meta のキー
+pub const MERGE_CONFIG_KEY: &str = "merge_config";
+
+/// マージ後のエントリー・決済時刻の決め方
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeTimePolicy {
+    #[default]
+    Weighted, // ロットで加重平均した時刻
+    Span,     // 最初のエントリーと最後の決済
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct MergeConfig {
+    pub time_policy: MergeTimePolicy,
+    pub auto_merge: AutoMergeRule,
+}
+
+/// 取り込み時に同時刻の約定を自動でまとめる条件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct AutoMergeRule {
+    pub enabled: bool,
+    pub window_secs: i64,               // エントリー・決済時刻の許容差
+    pub require_same_entry_rate: bool,  // 建値が同じものだけまとめる
+    pub max_lot: Option<f64>,           // まとめた後のロットの上限
+    pub disabled_accounts: Vec<String>, // 自動マージしない口座
+}
+
+impl Default for AutoMergeRule {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            window_secs: 1,
+            require_same_entry_rate: false,
+            max_lot: None,
+            disabled_accounts: Vec::new(),
+        }
+    }
+}
+
+impl MergeConfig {
+    /// 保存前に設定を確かめる
+    pub fn validate(&self) -> Result<(), String> {
+        if self.auto_merge.window_secs < 0 {
+            return Err("自動マージの時刻の許容差は 0 秒以上にしてください".into());
+        }
+        if self.auto_merge.max_lot.is_some_and(|max| max <= 0.0) {
+            return Err("自動マージのロットの上限は 0 より大きい値にしてください".into());
+        }
+        Ok(())
+    }
+}
+
+impl AutoMergeRule {
+    pub fn applies_to(&self, account: &str) -> bool {
+        self.enabled && !self.disabled_accounts.iter().any(|a| a == account)
+    }
+}
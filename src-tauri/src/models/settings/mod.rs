@@ -0,0 +1,4 @@
+pub mod analysis;
+pub mod market_data;
+pub mod merge;
+pub mod sync_scheduler;
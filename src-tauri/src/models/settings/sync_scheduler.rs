@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::service::sync::SyncKind;
+
+// 設定を保存する meta のキー
+pub const SYNC_SCHEDULER_CONFIG_KEY: &str = "sync_scheduler_config";
+
+// 同期の間隔の下限（秒）
+pub const MIN_SYNC_INTERVAL_SECS: u64 = 60;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct SyncSchedulerConfig {
+    pub enabled: bool,
+    pub interval_secs: u64,   // 同期の間隔（秒）
+    pub kinds: Vec<SyncKind>, // 定期同期の対象
+}
+
+impl Default for SyncSchedulerConfig {
+    fn default() -> Self {
+        SyncSchedulerConfig {
+            enabled: true,
+            interval_secs: 300,
+            // ティックはブリッジ側の負荷が大きいので既定では手動のみ
+            kinds: vec![SyncKind::Ohlc],
+        }
+    }
+}
+
+impl SyncSchedulerConfig {
+    /// 保存前に設定を確かめる
+    pub fn validate(&self) -> Result<(), String> {
+        if self.interval_secs < MIN_SYNC_INTERVAL_SECS {
+            return Err(format!(
+                "同期の間隔は {} 秒以上にしてください",
+                MIN_SYNC_INTERVAL_SECS
+            ));
+        }
+        Ok(())
+    }
+}
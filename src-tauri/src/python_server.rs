@@ -1,48 +1,414 @@
+use directories::ProjectDirs;
+use reqwest::Client;
+use std::fs::{File, OpenOptions};
+use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
-/// exe 起動時に venv をセットアップして Python サーバーを起動する
-pub fn start_python_server() -> Result<Child, String> {
-    let exe_dir = std::env::current_exe()
-        .expect("Failed to get exe path")
-        .parent()
-        .expect("Failed to get exe dir")
-        .to_path_buf();
+use crate::market_data::MarketDataState;
+use crate::models::service::bridge_status::{BridgeState, BridgeStatus};
+use crate::models::settings::market_data::{MarketDataConfig, ProviderKind};
 
-    let server_exe = exe_dir.join("mt5").join("mt5_server.exe");
+// ブリッジの状態を通知するイベント名
+pub const BRIDGE_STATUS_EVENT: &str = "bridge-status";
 
-    // Python サーバー起動
-    #[cfg(windows)]
-    let child = {
-        let mut cmd = Command::new(&server_exe);
-        #[cfg(debug_assertions)]
-        {
-            cmd.arg(&server_exe)
-                .stdout(Stdio::inherit()) 
-                .stderr(Stdio::inherit()); 
-            cmd.spawn().map_err(|e| format!("Failed to start Python server: {}", e))?
+// 死活確認の間隔
+const HEALTH_INTERVAL_SECS: u64 = 5;
+
+// MT5 の初期化を待つ間は応答がなくても異常とみなさない
+const STARTUP_GRACE_SECS: i64 = 30;
+
+// 起動後にこれだけ応答がなければハングとみなして再起動する
+const UNHEALTHY_RESTART_SECS: i64 = 60;
+
+// クラッシュ後の再起動は 1, 2, 4 ... 最大 60 秒待つ
+const MAX_BACKOFF_SECS: i64 = 60;
+
+// これだけ応答が続いたらバックオフをリセットする
+const STABLE_SECS: i64 = 60;
+
+// 終了要求からプロセス終了を待つ時間
+const SHUTDOWN_TIMEOUT_MS: u64 = 5000;
+
+// ログファイルがこれを超えたら起動時にローテーションする
+const MAX_LOG_BYTES: u64 = 10 * 1024 * 1024;
+
+#[cfg(windows)]
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+
+struct Inner {
+    child: Option<Child>,
+    status: BridgeStatus,
+    crash_count: u32,
+    spawned_at: Option<i64>,
+    healthy_since: Option<i64>,
+}
+
+/// MT5 ブリッジ (mt5_server) のプロセスを起動・監視する
+pub struct BridgeSupervisor {
+    inner: Mutex<Inner>,
+    client: Client,
+    shutting_down: AtomicBool,
+    restarting: AtomicBool,
+}
+
+impl BridgeSupervisor {
+    pub fn new() -> Self {
+        let status = BridgeStatus {
+            log_path: log_path().map(|p| p.to_string_lossy().to_string()),
+            ..Default::default()
+        };
+
+        BridgeSupervisor {
+            inner: Mutex::new(Inner {
+                child: None,
+                status,
+                crash_count: 0,
+                spawned_at: None,
+                healthy_since: None,
+            }),
+            client: Client::new(),
+            shutting_down: AtomicBool::new(false),
+            restarting: AtomicBool::new(false),
         }
+    }
+
+    pub fn status(&self) -> Result<BridgeStatus, String> {
+        let inner = self.inner.lock().map_err(|e| e.to_string())?;
+        Ok(inner.status.clone())
+    }
 
-        #[cfg(not(debug_assertions))]
+    /// 手動での再起動。バックオフを待たずにすぐ起動し直す
+    /// 終了を待つ間はロックを放し、監視が別のプロセスを起動しないようにする
+    pub fn restart(&self, config: &MarketDataConfig) -> Result<BridgeStatus, String> {
+        self.restarting.store(true, Ordering::SeqCst);
+        let result = self.restart_now(config);
+        self.restarting.store(false, Ordering::SeqCst);
+        result
+    }
+
+    fn restart_now(&self, config: &MarketDataConfig) -> Result<BridgeStatus, String> {
+        self.stop_child()?;
+
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        inner.crash_count = 0;
+        inner.status.next_restart_at = None;
+        if inner.child.is_none() {
+            spawn(&mut inner, config, now());
+        }
+        Ok(inner.status.clone())
+    }
+
+    /// 管理しているプロセスをロックの外で終了させる
+    fn stop_child(&self) -> Result<(), String> {
+        let child = self.inner.lock().map_err(|e| e.to_string())?.child.take();
+        if let Some(mut child) = child {
+            kill_tree(&mut child);
+        }
+        Ok(())
+    }
+
+    /// /shutdown で終了を依頼し、応答がなければプロセスツリーごと終了させる
+    pub async fn shutdown(&self, config: &MarketDataConfig) {
+        self.shutting_down.store(true, Ordering::SeqCst);
+
+        let has_child = self
+            .inner
+            .lock()
+            .map(|inner| inner.child.is_some())
+            .unwrap_or(false);
+        if !has_child {
+            return;
+        }
+
+        let _ = self
+            .client
+            .post(format!("{}/shutdown", config.base_url()))
+            .timeout(Duration::from_secs(2))
+            .send()
+            .await;
+
+        let mut waited = 0;
+        while waited < SHUTDOWN_TIMEOUT_MS {
+            let exited = match self.inner.lock() {
+                Ok(mut inner) => match inner.child.as_mut() {
+                    Some(child) => matches!(child.try_wait(), Ok(Some(_))),
+                    None => true,
+                },
+                Err(_) => true,
+            };
+            if exited {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            waited += 100;
+        }
+
+        let _ = self.stop_child();
+        if let Ok(mut inner) = self.inner.lock() {
+            inner.status.state = BridgeState::Stopped;
+            inner.status.pid = None;
+        }
+        println!("Python server stopped");
+    }
+
+    /// 1 回分の監視。状態が変わったら Some を返す
+    async fn check(&self, market: &MarketDataState) -> Result<Option<BridgeStatus>, String> {
+        if self.shutting_down.load(Ordering::SeqCst) || self.restarting.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        let config = market.config()?;
+        let before = self.status()?;
+
+        if config.provider != ProviderKind::Mt5 {
+            // MT5 を使わなくなったら起動したブリッジは止める
+            self.stop_child()?;
+            let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+            inner.crash_count = 0;
+            inner.spawned_at = None;
+            inner.healthy_since = None;
+            inner.status.state = BridgeState::Disabled;
+            inner.status.managed = false;
+            inner.status.pid = None;
+            inner.status.next_restart_at = None;
+            return Ok(changed(&before, &inner.status));
+        }
+
+        let managed = is_local(&config.host);
         {
-            cmd.arg(&server_exe)
-                .stdout(Stdio::piped())
-                .stderr(Stdio::piped())
-                .creation_flags(0x08000000)
-                .spawn()
-                .map_err(|e| format!("Failed to start Python server: {}", e))?
+            let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+            let now = now();
+            inner.status.managed = managed;
+            reap(&mut inner, now);
+
+            let due = inner.status.next_restart_at.is_none_or(|t| t <= now);
+            let restarting = self.restarting.load(Ordering::SeqCst);
+            if managed && inner.child.is_none() && due && !restarting {
+                spawn(&mut inner, &config, now);
+            }
+        }
+
+        let reachable = market.provider()?.ping().await.is_ok();
+
+        let mut inner = self.inner.lock().map_err(|e| e.to_string())?;
+        let now = now();
+        let mut hung = None;
+        if reachable {
+            inner.status.state = BridgeState::Running;
+            inner.status.last_healthy_at = Some(now);
+            let since = *inner.healthy_since.get_or_insert(now);
+            if now - since >= STABLE_SECS {
+                inner.crash_count = 0;
+            }
+        } else {
+            inner.healthy_since = None;
+            let in_grace = inner
+                .spawned_at
+                .is_some_and(|t| now - t < STARTUP_GRACE_SECS);
+            let last_seen = inner
+                .status
+                .last_healthy_at
+                .max(inner.spawned_at)
+                .unwrap_or(now);
+
+            if inner.child.is_some() && !in_grace && now - last_seen >= UNHEALTHY_RESTART_SECS {
+                // 応答しないまま動き続けているので止めて再起動させる（終了はロックの外で待つ）
+                hung = inner.child.take();
+                mark_crashed(&mut inner, "応答がないため停止しました".to_string(), now);
+            } else if inner.child.is_some() && in_grace {
+                inner.status.state = BridgeState::Starting;
+            } else if inner.child.is_some() || !managed || inner.status.state == BridgeState::Running {
+                inner.status.state = BridgeState::Unreachable;
+            }
+        }
+
+        let status = changed(&before, &inner.status);
+        drop(inner);
+        if let Some(mut child) = hung {
+            kill_tree(&mut child);
+        }
+        Ok(status)
+    }
+}
+
+/// アプリ終了まで MT5 ブリッジを監視し続ける
+pub async fn run_supervisor(app: AppHandle) {
+    loop {
+        let supervisor = app.state::<BridgeSupervisor>();
+        let market = app.state::<MarketDataState>();
+
+        match supervisor.check(&market).await {
+            Ok(Some(status)) => {
+                let _ = app.emit(BRIDGE_STATUS_EVENT, status);
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("❌ Bridge health check failed: {}", e),
         }
+
+        tokio::time::sleep(Duration::from_secs(HEALTH_INTERVAL_SECS)).await;
+    }
+}
+
+/// 終了したプロセスを回収し、再起動を予約する
+fn reap(inner: &mut Inner, now: i64) {
+    let exited = match inner.child.as_mut() {
+        Some(child) => match child.try_wait() {
+            Ok(Some(status)) => Some(status.to_string()),
+            Ok(None) => None,
+            Err(e) => Some(e.to_string()),
+        },
+        None => None,
     };
 
+    if let Some(reason) = exited {
+        inner.child = None;
+        eprintln!("Python server exited: {}", reason);
+        mark_crashed(inner, reason, now);
+    }
+}
+
+fn mark_crashed(inner: &mut Inner, reason: String, now: i64) {
+    let backoff = 2i64.pow(inner.crash_count.min(6)).min(MAX_BACKOFF_SECS);
+    inner.crash_count += 1;
+    inner.spawned_at = None;
+    inner.healthy_since = None;
+    inner.status.state = BridgeState::Crashed;
+    inner.status.pid = None;
+    inner.status.last_exit = Some(reason);
+    inner.status.next_restart_at = Some(now + backoff);
+}
+
+fn spawn(inner: &mut Inner, config: &MarketDataConfig, now: i64) {
+    let server_exe = match server_path() {
+        Some(path) => path,
+        None => {
+            // ブリッジを同梱していない環境では外部で起動したものを監視するだけ
+            inner.status.state = BridgeState::NotInstalled;
+            inner.status.next_restart_at = None;
+            return;
+        }
+    };
+
+    match start_python_server(&server_exe, config) {
+        Ok(child) => {
+            println!("Python server started (pid = {})", child.id());
+            if inner.spawned_at.is_some() || inner.status.last_exit.is_some() {
+                inner.status.restarts += 1;
+            }
+            inner.status.pid = Some(child.id());
+            inner.status.state = BridgeState::Starting;
+            inner.status.last_error = None;
+            inner.status.next_restart_at = None;
+            inner.spawned_at = Some(now);
+            inner.child = Some(child);
+        }
+        Err(e) => {
+            eprintln!("Failed to start Python server: {}", e);
+            mark_crashed(inner, e.clone(), now);
+            inner.status.last_error = Some(e);
+        }
+    }
+}
+
+/// Python サーバーを起動し、標準出力・標準エラーをログファイルに書き出す
+fn start_python_server(server_exe: &PathBuf, config: &MarketDataConfig) -> Result<Child, String> {
+    let (stdout, stderr) = open_log()?;
+
+    let mut cmd = Command::new(server_exe);
+    cmd.arg("--host")
+        .arg(&config.host)
+        .arg("--port")
+        .arg(config.port.to_string())
+        .env("PYTHONUNBUFFERED", "1")
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr);
+
+    #[cfg(all(windows, not(debug_assertions)))]
+    cmd.creation_flags(CREATE_NO_WINDOW);
+
+    cmd.spawn()
+        .map_err(|e| format!("Failed to start Python server: {}", e))
+}
+
+/// exe と同じ場所の mt5/ にある mt5_server を探す
+fn server_path() -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe().ok()?.parent()?.to_path_buf();
+
+    #[cfg(windows)]
+    let server_exe = exe_dir.join("mt5").join("mt5_server.exe");
+
     #[cfg(not(windows))]
-    let child = Command::new(&server_exe)
-        .arg(&server_path)
-        .stdout(Stdio::piped())
-        .stderr(Stdio::piped())
-        .spawn()
-        .map_err(|e| format!("Failed to start Python server: {}", e))?;
-        println!("Python server started (pid = {})", child.id());
-    Ok(child)
+    let server_exe = exe_dir.join("mt5").join("mt5_server");
+
+    server_exe.is_file().then_some(server_exe)
+}
+
+fn log_path() -> Option<PathBuf> {
+    #[cfg(debug_assertions)]
+    let dir = PathBuf::from("..");
+
+    #[cfg(not(debug_assertions))]
+    let dir = ProjectDirs::from("com", "example", "fx-trade-manager")?
+        .data_dir()
+        .join("logs");
+
+    Some(dir.join("mt5_server.log"))
+}
+
+fn open_log() -> Result<(File, File), String> {
+    let path = log_path().ok_or("ログの保存先が取得できません")?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    // 大きくなったら 1 世代だけ残す
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let _ = std::fs::rename(&path, path.with_extension("log.1"));
+    }
+
+    let stdout = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| e.to_string())?;
+    let stderr = stdout.try_clone().map_err(|e| e.to_string())?;
+    Ok((stdout, stderr))
+}
+
+/// PyInstaller の exe は子プロセスを持つのでツリーごと終了させる
+fn kill_tree(child: &mut Child) {
+    #[cfg(windows)]
+    {
+        let _ = Command::new("taskkill")
+            .args(["/PID", &child.id().to_string(), "/T", "/F"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .status();
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+fn is_local(host: &str) -> bool {
+    matches!(host, "127.0.0.1" | "localhost" | "::1" | "0.0.0.0")
+}
+
+fn changed(before: &BridgeStatus, after: &BridgeStatus) -> Option<BridgeStatus> {
+    let same = before.state == after.state
+        && before.pid == after.pid
+        && before.restarts == after.restarts
+        && before.managed == after.managed;
+    (!same).then(|| after.clone())
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
 }
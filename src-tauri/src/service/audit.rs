@@ -0,0 +1,58 @@
+use crate::db::queries::audit;
+use crate::db::DbState;
+use crate::models::db::audit::AuditEntry;
+use serde_json::Value;
+
+// 削除・マージ・分割の状態を表す列。書き戻すと元のトレードとの関係が崩れる
+const LINEAGE_COLUMNS: [&str; 3] = ["is_deleted", "merged_to", "split_from"];
+
+/// 取引とそのラベルの変更履歴
+pub fn get_trade_history(db: &DbState, trade_id: i64) -> Result<Vec<AuditEntry>, String> {
+    audit::find_by_trade_id(db, trade_id)
+}
+
+/// 日別メモの変更履歴
+pub fn get_daily_memo_history(db: &DbState, date: &str) -> Result<Vec<AuditEntry>, String> {
+    audit::find_by_daily_memo(db, date)
+}
+
+/// 1 件の変更を取り消し、変更前の状態に戻す
+/// 追加は削除（取引は削除済み）に、更新・削除は変更前の行に戻す
+/// 取引の削除・マージ・分割の状態が変わる取り消しはしない
+pub fn revert_change(db: &DbState, audit_id: i64) -> Result<(), String> {
+    let entry = audit::find_by_id(db, audit_id)?.ok_or("履歴が見つかりません")?;
+    if touches_lineage(&entry) {
+        return Err(
+            "削除・マージ・分割に関わる変更は履歴から戻せません。ゴミ箱からの復元かマージの取り消しを使ってください"
+                .into(),
+        );
+    }
+
+    match (&entry.before, &entry.after) {
+        (Some(before), _) => audit::restore_row(db, &entry.table_name, &before.to_string()),
+        (None, Some(after)) => audit::remove_row(db, &entry.table_name, &after.to_string()),
+        (None, None) => Err("戻せる内容がありません".into()),
+    }
+}
+
+/// 取り消すと取引の is_deleted / merged_to / split_from が変わるか
+fn touches_lineage(entry: &AuditEntry) -> bool {
+    if entry.table_name != "trades" {
+        return false;
+    }
+
+    let is_unset = |v: Option<&Value>| match v {
+        None | Some(Value::Null) => true,
+        Some(v) => v.as_i64() == Some(0),
+    };
+    match (&entry.before, &entry.after) {
+        (Some(before), Some(after)) => LINEAGE_COLUMNS
+            .iter()
+            .any(|c| before.get(c) != after.get(c)),
+        // 追加の取り消しは削除済みにする
+        (None, Some(_)) => true,
+        // 削除の取り消しは変更前の状態で作り直す
+        (Some(before), None) => LINEAGE_COLUMNS.iter().any(|c| !is_unset(before.get(c))),
+        (None, None) => false,
+    }
+}
@@ -0,0 +1,239 @@
+use crate::db::queries::{campaigns, excursions, trades};
+use crate::db::DbState;
+use crate::models::db::campaign::Campaign;
+use crate::models::db::excursion::TradeExcursion;
+use crate::models::db::trade::Trade;
+use crate::models::service::campaign_summary::CampaignSummary;
+use crate::models::service::trade_summary::TradeSummary;
+use crate::utils::time_utils::unix_to_jst_str;
+use std::collections::HashMap;
+
+/// 取引をまとめてキャンペーンを作る。name を省略したら通貨ペアと最初のエントリー時刻から付ける
+pub fn create_campaign(
+    db: &DbState,
+    name: Option<String>,
+    mut trade_ids: Vec<i64>,
+    memo: Option<String>,
+) -> Result<CampaignSummary, String> {
+    trade_ids.sort();
+    trade_ids.dedup();
+    let trades = trades::get_by_ids(db, trade_ids.clone())?;
+    validate(&trades, &trade_ids, None, None)?;
+
+    let campaign = Campaign {
+        name: name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| default_name(&trades)),
+        pair: trades[0].pair.clone(),
+        side: trades[0].side.clone(),
+        memo: memo.unwrap_or_default(),
+        auto: false,
+        created_at: chrono::Utc::now().timestamp(),
+        ..Default::default()
+    };
+    let id = campaigns::insert_campaign_with_trades(db, &campaign, &trade_ids)?;
+
+    get_campaign(db, id)
+}
+
+pub fn update_campaign(db: &DbState, id: i64, name: String, memo: String) -> Result<(), String> {
+    let campaign = campaigns::find_by_id(db, id)?.ok_or("キャンペーンが見つかりません")?;
+    if name.trim().is_empty() {
+        return Err("キャンペーン名を入力してください".into());
+    }
+
+    campaigns::update_campaign(
+        db,
+        &Campaign {
+            name,
+            memo,
+            ..campaign
+        },
+    )
+}
+
+pub fn delete_campaign(db: &DbState, id: i64) -> Result<(), String> {
+    campaigns::delete_campaign(db, id)
+}
+
+pub fn add_trades(
+    db: &DbState,
+    id: i64,
+    mut trade_ids: Vec<i64>,
+) -> Result<CampaignSummary, String> {
+    let campaign = campaigns::find_by_id(db, id)?.ok_or("キャンペーンが見つかりません")?;
+    trade_ids.sort();
+    trade_ids.dedup();
+    let trades = trades::get_by_ids(db, trade_ids.clone())?;
+    let account = trades::get_trades_by_campaign(db, id)?
+        .into_iter()
+        .next()
+        .map(|t| t.account);
+    validate(&trades, &trade_ids, Some(&campaign), account.as_deref())?;
+
+    campaigns::add_trades(db, id, &trade_ids)?;
+    get_campaign(db, id)
+}
+
+pub fn remove_trades(
+    db: &DbState,
+    id: i64,
+    trade_ids: Vec<i64>,
+) -> Result<CampaignSummary, String> {
+    campaigns::remove_trades(db, id, &trade_ids)?;
+    get_campaign(db, id)
+}
+
+pub fn get_campaign(db: &DbState, id: i64) -> Result<CampaignSummary, String> {
+    let campaign = campaigns::find_by_id(db, id)?.ok_or("キャンペーンが見つかりません")?;
+    let trades = trades::get_trades_by_campaign(db, id)?;
+    summarize(campaign, trades, &excursions::get_all_by_trade_id(db)?)
+}
+
+pub fn get_campaigns(db: &DbState) -> Result<Vec<CampaignSummary>, String> {
+    let excursions = excursions::get_all_by_trade_id(db)?;
+    let mut result = Vec::new();
+    for campaign in campaigns::get_all_campaigns(db)? {
+        let trades = trades::get_trades_by_campaign(db, campaign.id.unwrap_or(0))?;
+        result.push(summarize(campaign, trades, &excursions)?);
+    }
+    Ok(result)
+}
+
+/// まだキャンペーンに入っていない取引のうち、保有期間が重なる同じ方向の取引をまとめる
+pub fn auto_group(db: &DbState) -> Result<Vec<CampaignSummary>, String> {
+    let mut ungrouped = trades::get_ungrouped_trades(db)?;
+    ungrouped.sort_by_key(|t| (t.entry_time, t.id));
+
+    // (口座, 通貨ペア, 売買) ごとに、決済前に次のエントリーがあれば同じグループ
+    let mut groups: Vec<(Vec<Trade>, i64)> = Vec::new();
+    for trade in ungrouped {
+        let open = groups.iter().rposition(|(g, last_exit)| {
+            let first = &g[0];
+            first.account == trade.account
+                && first.pair == trade.pair
+                && first.side == trade.side
+                && trade.entry_time < *last_exit
+        });
+
+        match open {
+            Some(i) => {
+                groups[i].1 = groups[i].1.max(trade.exit_time);
+                groups[i].0.push(trade);
+            }
+            None => {
+                let exit = trade.exit_time;
+                groups.push((vec![trade], exit));
+            }
+        }
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let excursions = excursions::get_all_by_trade_id(db)?;
+    let mut result = Vec::new();
+    for (group, _) in groups.into_iter().filter(|(g, _)| g.len() >= 2) {
+        let campaign = Campaign {
+            name: default_name(&group),
+            pair: group[0].pair.clone(),
+            side: group[0].side.clone(),
+            auto: true,
+            created_at: now,
+            ..Default::default()
+        };
+        let trade_ids: Vec<i64> = group.iter().filter_map(|t| t.id).map(|id| id as i64).collect();
+        let id = campaigns::insert_campaign_with_trades(db, &campaign, &trade_ids)?;
+
+        result.push(summarize(Campaign { id: Some(id), ..campaign }, group, &excursions)?);
+    }
+
+    Ok(result)
+}
+
+/// account はキャンペーンに入っている取引の口座（まだなければ None）
+fn validate(
+    trades: &Vec<Trade>,
+    trade_ids: &[i64],
+    campaign: Option<&Campaign>,
+    account: Option<&str>,
+) -> Result<(), String> {
+    if trades.is_empty() || trades.len() != trade_ids.len() {
+        return Err("トレードが見つかりません".into());
+    }
+
+    let (pair, side) = match campaign {
+        Some(c) => (&c.pair, &c.side),
+        None => (&trades[0].pair, &trades[0].side),
+    };
+    if trades.iter().any(|t| t.pair != *pair) {
+        return Err("通貨ペアが異なるトレードはまとめられません".into());
+    }
+    if trades.iter().any(|t| t.side != *side) {
+        return Err("売買方向が異なるトレードはまとめられません".into());
+    }
+    let account = account.unwrap_or(&trades[0].account);
+    if trades.iter().any(|t| t.account != account) {
+        return Err("口座が異なるトレードはまとめられません".into());
+    }
+
+    Ok(())
+}
+
+fn default_name(trades: &[Trade]) -> String {
+    let first_entry = trades.iter().map(|t| t.entry_time).min().unwrap_or(0);
+    format!(
+        "{} {} {}",
+        trades[0].pair,
+        trades[0].side,
+        unix_to_jst_str(first_entry)
+    )
+}
+
+fn summarize(
+    campaign: Campaign,
+    trades: Vec<Trade>,
+    excursions: &HashMap<i64, TradeExcursion>,
+) -> Result<CampaignSummary, String> {
+    let mut summary = TradeSummary::from_trades(trades.clone());
+    summary.apply_excursions(excursions);
+
+    let total_lot: f64 = trades.iter().map(|t| t.lot).sum();
+    let weighted = |f: fn(&Trade) -> f64| {
+        if total_lot > 0.0 {
+            trades.iter().map(|t| f(t) * t.lot).sum::<f64>() / total_lot
+        } else {
+            0.0
+        }
+    };
+
+    Ok(CampaignSummary {
+        total_lot: (total_lot * 1e8).round() / 1e8,
+        max_exposure: max_exposure(&trades),
+        avg_entry_rate: weighted(|t| t.entry_rate),
+        avg_exit_rate: weighted(|t| t.exit_rate),
+        profit: trades.iter().map(|t| t.profit).sum(),
+        swap: trades.iter().filter_map(|t| t.swap).sum(),
+        first_entry: trades.iter().map(|t| t.entry_time).min().unwrap_or(0),
+        last_exit: trades.iter().map(|t| t.exit_time).max().unwrap_or(0),
+        campaign,
+        summary,
+    })
+}
+
+/// 同時に保有していたロットの最大。同じ時刻なら決済を先に数える
+fn max_exposure(trades: &[Trade]) -> f64 {
+    let mut events: Vec<(i64, f64)> = Vec::new();
+    for t in trades {
+        events.push((t.entry_time, t.lot));
+        events.push((t.exit_time, -t.lot));
+    }
+    events.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.total_cmp(&b.1)));
+
+    let mut exposure = 0.0;
+    let mut max = 0.0f64;
+    for (_, lot) in events {
+        exposure += lot;
+        max = max.max(exposure);
+    }
+
+    (max * 1e8).round() / 1e8
+}
@@ -1,16 +1,20 @@
 use crate::db::DbState;
 use crate::models::db::candle::Candle;
-use rusqlite::Result;
-use std::collections::BTreeMap;
+use crate::models::settings::market_data::BASE_TIMEFRAME;
+use rusqlite::{params, Result};
 
-pub fn fetch_candles(db: &DbState, interval: i64) -> Result<Vec<Candle>, String> {
+pub fn fetch_candles(db: &DbState, pair: &str, interval: i64) -> Result<Vec<Candle>, String> {
     let conn = db.conn.lock().unwrap();
     let mut stmt = conn
-        .prepare("SELECT time, open, high, low, close FROM candles ORDER BY time ASC")
+        .prepare(
+            "SELECT time, open, high, low, close FROM candles
+            WHERE pair = ?1 AND timeframe = ?2
+            ORDER BY time ASC",
+        )
         .map_err(|e| e.to_string())?;
 
     let iter = stmt
-        .query_map([], |row| {
+        .query_map(params![pair, BASE_TIMEFRAME], |row| {
             Ok(Candle {
                 time: row.get::<_, i64>(0)?, // UNIX秒
                 open: row.get::<_, f64>(1)?,
@@ -32,33 +36,60 @@ pub fn fetch_candles(db: &DbState, interval: i64) -> Result<Vec<Candle>, String>
     }
 
     // --- 集約処理 ---
-    let mut grouped: BTreeMap<i64, Vec<&Candle>> = BTreeMap::new();
-
+    let mut aggregator = CandleAggregator::new(interval);
+    let mut aggregated = Vec::new();
     for candle in &raw_candles {
-        let key = candle.time - (candle.time % interval);
-        grouped.entry(key).or_default().push(candle);
+        if let Some(bar) = aggregator.push(candle) {
+            aggregated.push(bar);
+        }
+    }
+    if let Some(bar) = aggregator.finish() {
+        aggregated.push(bar);
     }
 
-    let mut aggregated = Vec::new();
-    for (start_time, group) in grouped {
-        if group.is_empty() {
-            continue;
+    Ok(aggregated)
+}
+
+/// 時刻昇順に流れてくるローソク足を interval 秒ごとに集約する
+pub struct CandleAggregator {
+    interval: i64,
+    current: Option<Candle>,
+}
+
+impl CandleAggregator {
+    pub fn new(interval: i64) -> Self {
+        CandleAggregator {
+            interval,
+            current: None,
         }
+    }
 
-        let open = group.first().unwrap().open;
-        let close = group.last().unwrap().close;
-        let high = group.iter().map(|c| c.high).fold(f64::MIN, f64::max);
-        let low = group.iter().map(|c| c.low).fold(f64::MAX, f64::min);
+    /// 足を追加し、区切りを越えて確定した集約足があれば返す
+    pub fn push(&mut self, candle: &Candle) -> Option<Candle> {
+        let key = candle.time - (candle.time % self.interval);
 
-        aggregated.push(Candle {
-            time: start_time,
-            open,
-            high,
-            low,
-            close,
+        if let Some(current) = self.current.as_mut() {
+            if current.time == key {
+                current.high = current.high.max(candle.high);
+                current.low = current.low.min(candle.low);
+                current.close = candle.close;
+                return None;
+            }
+        }
+
+        let started = Candle {
+            time: key,
+            open: candle.open,
+            high: candle.high,
+            low: candle.low,
+            close: candle.close,
             ..Default::default()
-        });
+        };
+        self.current.replace(started)
     }
 
-    Ok(aggregated)
+    /// 集約中の足を確定して返す
+    pub fn finish(&mut self) -> Option<Candle> {
+        self.current.take()
+    }
 }
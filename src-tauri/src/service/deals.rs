@@ -0,0 +1,161 @@
+use crate::db::queries::{deals, symbols};
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::db::deal::{
+    Deal, DEAL_ENTRY_IN, DEAL_ENTRY_INOUT, DEAL_ENTRY_OUT, DEAL_ENTRY_OUT_BY, DEAL_TYPE_BUY,
+    DEAL_TYPE_SELL,
+};
+use crate::models::db::record::Record;
+use crate::models::service::deal_import::DealImportSummary;
+use crate::service::import::{build_trades, load_auto_merge_rule, save_trade, LOT_UNITS};
+use crate::service::symbols::market_symbol;
+
+// MT5 から取り込んだ取引の口座名
+pub const MT5_ACCOUNT: &str = "MT5";
+
+// カタログにない銘柄の 1 ロットの通貨量（MT5 の FX 標準）
+const DEFAULT_CONTRACT_SIZE: f64 = 100_000.0;
+
+/// ブリッジから約定履歴を取り込み、決済が増えたポジションの取引を追加する
+pub async fn import_deals(
+    db: &DbState,
+    market: &MarketDataState,
+) -> Result<DealImportSummary, String> {
+    let provider = market.provider()?;
+    let since = deals::get_latest_time(db)?;
+    let fetched = provider.fetch_deals(since).await?;
+    let new_tickets = deals::insert_deals_bulk(db, &fetched)?;
+    let rule = load_auto_merge_rule(db)?;
+
+    let mut summary = DealImportSummary {
+        fetched: fetched.len(),
+        new_deals: new_tickets.len(),
+        ..Default::default()
+    };
+
+    // 新しい決済約定を含むポジションと、前回までに組み立てられなかったポジションを組み直す
+    let mut position_ids: Vec<i64> = fetched
+        .iter()
+        .filter(|d| d.is_exit() && new_tickets.contains(&d.ticket))
+        .map(|d| d.position_id)
+        .collect();
+    position_ids.extend(deals::find_unlinked_exit_positions(db)?);
+    position_ids.sort();
+    position_ids.dedup();
+
+    for position_id in position_ids {
+        let position_deals = deals::find_by_position(db, position_id)?;
+        let lot_scale = match position_deals.first() {
+            Some(d) => lot_scale(db, &d.symbol)?,
+            None => continue,
+        };
+
+        let records = to_records(&position_deals, lot_scale);
+        let trades = match build_trades(db, records, MT5_ACCOUNT) {
+            Ok(trades) => trades,
+            Err(e) => {
+                // 建玉が履歴の取得範囲より前だと突き合わせられない
+                summary.errors.push(format!("position {}: {}", position_id, e));
+                continue;
+            }
+        };
+
+        for (trade, ticket) in trades {
+            let ticket = match ticket {
+                Some(t) => t,
+                None => continue,
+            };
+            let imported = position_deals
+                .iter()
+                .any(|d| d.ticket == ticket && d.trade_id.is_some());
+            if imported {
+                continue;
+            }
+
+            let (trade_id, inserted, merged) = save_trade(db, trade, &rule)?;
+            deals::update_trade_id(db, ticket, trade_id)?;
+            if inserted {
+                summary.trades += 1;
+            }
+            summary.auto_merges.extend(merged);
+        }
+    }
+
+    Ok(summary)
+}
+
+/// MT5 の 1 ロットをアプリのロット（LOT_UNITS 通貨）に換算する倍率
+fn lot_scale(db: &DbState, symbol: &str) -> Result<f64, String> {
+    let contract_size = symbols::find_by_name(db, &market_symbol(symbol))?
+        .map(|s| s.contract_size)
+        .filter(|size| *size > 0.0)
+        .unwrap_or(DEFAULT_CONTRACT_SIZE);
+    Ok(contract_size / LOT_UNITS)
+}
+
+/// 1 ポジション分の約定を取り込み用の Record にする
+/// CSV と揃えて、ロットはアプリのロットに換算し、損益にはスワップを含める
+/// ドテン（INOUT）は建玉分の決済と残りの新規に分ける
+fn to_records(position_deals: &[Deal], lot_scale: f64) -> Vec<Record> {
+    let mut records = Vec::new();
+    let mut open_lot = 0.0;
+
+    for deal in position_deals {
+        let side = match deal.deal_type {
+            DEAL_TYPE_BUY => "買",
+            DEAL_TYPE_SELL => "売",
+            _ => continue,
+        };
+
+        // 0.1 * 10 のような誤差が残らないよう丸める
+        let lot = (deal.volume * lot_scale * 1e8).round() / 1e8;
+        let record = Record {
+            pair: deal.symbol.clone(),
+            side: side.to_string(),
+            lot,
+            rate: deal.price,
+            profit: Some((deal.profit + deal.swap).round() as i32),
+            swap: Some(deal.swap.round() as i32),
+            order_time: deal.time,
+            ticket: Some(deal.ticket),
+            position_id: Some(deal.position_id),
+            ..Default::default()
+        };
+
+        match deal.entry {
+            DEAL_ENTRY_IN => {
+                open_lot += lot;
+                records.push(Record {
+                    trade_type: "新規".to_string(),
+                    ..record
+                });
+            }
+            DEAL_ENTRY_OUT | DEAL_ENTRY_OUT_BY => {
+                open_lot -= lot;
+                records.push(Record {
+                    trade_type: "決済".to_string(),
+                    ..record
+                });
+            }
+            DEAL_ENTRY_INOUT => {
+                let remaining = ((lot - open_lot) * 1e8).round() / 1e8;
+                records.push(Record {
+                    trade_type: "決済".to_string(),
+                    lot: open_lot,
+                    ..record.clone()
+                });
+                records.push(Record {
+                    trade_type: "新規".to_string(),
+                    lot: remaining,
+                    profit: None,
+                    swap: None,
+                    ..record
+                });
+                open_lot = remaining;
+            }
+            _ => {}
+        }
+    }
+
+    records
+}
@@ -0,0 +1,143 @@
+use crate::db::queries::{deals, trades};
+use crate::db::DbState;
+use crate::models::db::trade::Trade;
+use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::service::drawdown::Drawdown;
+use crate::models::service::equity::{
+    EquityCurve, EquityCurveOptions, EquityGranularity, EquityPoint,
+};
+use crate::utils::time_utils::get_business_date_from_unix;
+use std::collections::HashMap;
+
+// マージ・分割をたどる深さの上限
+const MAX_LINEAGE_DEPTH: usize = 16;
+
+/// 決済順に損益を積み上げた曲線と、最高値・ドローダウンの推移
+pub fn get_equity_curve(
+    db: &DbState,
+    filter: Option<TradeFilter>,
+    options: Option<EquityCurveOptions>,
+) -> Result<EquityCurve, String> {
+    let options = options.unwrap_or_default();
+    let mut trades = match filter {
+        Some(f) => trades::get_by_filter(db, f)?,
+        None => trades::get_all_trades(db)?,
+    };
+    trades.sort_by_key(|t| (t.exit_time, t.id));
+
+    let fees = if options.include_fees {
+        trade_fees(db)?
+    } else {
+        HashMap::new()
+    };
+    // profit はスワップ込みなので、含めないときはスワップを差し引く
+    let pnl_of = |t: &Trade| {
+        let mut pnl = t.profit as f64;
+        if !options.include_swap {
+            pnl -= t.swap.unwrap_or(0) as f64;
+        }
+        pnl + t
+            .id
+            .and_then(|id| fees.get(&(id as i64)))
+            .copied()
+            .unwrap_or(0.0)
+    };
+
+    // 曲線の各点: (時刻, 取引日, トレード, トレード数, 損益)
+    let mut steps: Vec<(i64, chrono::NaiveDate, Option<i64>, i32, f64)> = Vec::new();
+    for t in &trades {
+        let date = get_business_date_from_unix(t.exit_time);
+        let pnl = pnl_of(t);
+        match (options.granularity, steps.last_mut()) {
+            (EquityGranularity::Day, Some(last)) if last.1 == date => {
+                last.0 = t.exit_time;
+                last.3 += 1;
+                last.4 += pnl;
+            }
+            (EquityGranularity::Day, _) => steps.push((t.exit_time, date, None, 1, pnl)),
+            (EquityGranularity::Trade, _) => {
+                steps.push((t.exit_time, date, t.id.map(|id| id as i64), 1, pnl))
+            }
+        }
+    }
+
+    let start_time = trades.first().map(|t| t.entry_time).unwrap_or(0);
+    let mut curve = EquityCurve {
+        start_time,
+        ..Default::default()
+    };
+
+    let mut drawdown = Drawdown::new(options.starting_balance, start_time);
+    for (time, date, trade_id, trade_count, pnl) in steps {
+        let point = drawdown.step(time, pnl);
+        curve.points.push(EquityPoint {
+            time,
+            date: date.format("%Y-%m-%d").to_string(),
+            trade_id,
+            trade_count,
+            pnl,
+            equity: drawdown.equity,
+            peak: drawdown.peak,
+            drawdown: point.drawdown,
+            drawdown_pct: point.drawdown_pct,
+            drawdown_duration: point.duration,
+        });
+    }
+    curve.final_equity = drawdown.equity;
+    curve.max_drawdown = drawdown.max_drawdown;
+    curve.max_drawdown_pct = drawdown.max_drawdown_pct;
+    curve.max_drawdown_duration = drawdown.max_drawdown_duration;
+    curve.options = options;
+
+    Ok(curve)
+}
+
+/// トレードごとの手数料
+/// マージしたトレードは元のトレードの合計、分割したトレードは元のトレードをロットで按分する
+fn trade_fees(db: &DbState) -> Result<HashMap<i64, f64>, String> {
+    let mut direct: HashMap<i64, f64> = HashMap::new();
+    for (trade_id, fee) in deals::get_commissions_by_trade(db)? {
+        *direct.entry(trade_id).or_insert(0.0) += fee;
+    }
+    if direct.is_empty() {
+        return Ok(direct);
+    }
+
+    let lineage = trades::get_lineage(db)?;
+    let mut lots: HashMap<i64, (f64, i64)> = HashMap::new();
+    let mut merged_from: HashMap<i64, Vec<i64>> = HashMap::new();
+    for (id, lot, merged_to, split_from) in &lineage {
+        lots.insert(*id, (*lot, *split_from));
+        if let Some(parent) = merged_to {
+            merged_from.entry(*parent).or_default().push(*id);
+        }
+    }
+
+    fn fee_of(
+        id: i64,
+        depth: usize,
+        direct: &HashMap<i64, f64>,
+        lots: &HashMap<i64, (f64, i64)>,
+        merged_from: &HashMap<i64, Vec<i64>>,
+    ) -> f64 {
+        if depth > MAX_LINEAGE_DEPTH {
+            return 0.0;
+        }
+        let mut fee = direct.get(&id).copied().unwrap_or(0.0);
+        for child in merged_from.get(&id).into_iter().flatten() {
+            fee += fee_of(*child, depth + 1, direct, lots, merged_from);
+        }
+        if let Some((lot, split_from)) = lots.get(&id) {
+            if let Some((parent_lot, _)) = lots.get(split_from).filter(|(l, _)| *l > 0.0) {
+                fee += fee_of(*split_from, depth + 1, direct, lots, merged_from) * lot / parent_lot;
+            }
+        }
+        fee
+    }
+
+    Ok(lineage
+        .iter()
+        .map(|(id, ..)| (*id, fee_of(*id, 0, &direct, &lots, &merged_from)))
+        .filter(|(_, fee)| *fee != 0.0)
+        .collect())
+}
@@ -0,0 +1,209 @@
+use crate::db::queries::{candles, excursions, ticks, trades};
+use crate::db::DbState;
+use crate::models::db::excursion::{
+    TradeExcursion, EXCURSION_SOURCE_CANDLE, EXCURSION_SOURCE_TICK,
+};
+use crate::models::db::trade::Trade;
+use crate::models::service::excursion_compute::ExcursionComputeSummary;
+use crate::models::settings::market_data::BASE_TIMEFRAME;
+use crate::service::import::LOT_UNITS;
+use crate::service::symbols::{market_symbol, point_of};
+use std::collections::HashSet;
+
+// 1 回に読み込む件数
+const PAGE_SIZE: i64 = 50_000;
+
+/// 保有中の逆行・順行の最大を追う。値はエントリーからの価格差で 0 以上
+struct Tracker {
+    entry_rate: f64,
+    entry_time: i64,
+    direction: f64,
+    mae: f64,
+    mfe: f64,
+    mae_time: i64,
+    mfe_time: i64,
+}
+
+impl Tracker {
+    fn new(trade: &Trade) -> Self {
+        Self {
+            entry_rate: trade.entry_rate,
+            entry_time: trade.entry_time,
+            direction: if trade.side == "買" { 1.0 } else { -1.0 },
+            mae: 0.0,
+            mfe: 0.0,
+            mae_time: trade.entry_time,
+            mfe_time: trade.entry_time,
+        }
+    }
+
+    fn observe(&mut self, time: i64, adverse_price: f64, favorable_price: f64) {
+        let adverse = (self.entry_rate - adverse_price) * self.direction;
+        if adverse > self.mae {
+            self.mae = adverse;
+            self.mae_time = time;
+        }
+
+        let favorable = (favorable_price - self.entry_rate) * self.direction;
+        if favorable > self.mfe {
+            self.mfe = favorable;
+            self.mfe_time = time;
+        }
+    }
+}
+
+/// 1 件の取引の MAE/MFE を計算して保存する。相場データがなければ None
+pub fn compute_excursion(db: &DbState, trade: &Trade) -> Result<Option<TradeExcursion>, String> {
+    let trade_id = trade.id.ok_or("トレードが見つかりません")? as i64;
+    let pair = market_symbol(&trade.pair);
+    let is_buy = trade.side == "買";
+
+    let mut tracker = Tracker::new(trade);
+    let mut source = EXCURSION_SOURCE_TICK;
+
+    // 買いは bid、売りは ask で決済する前提で評価する
+    let found = scan_ticks(db, &pair, trade, |t| {
+        let price = if is_buy { t.bid } else { t.ask };
+        tracker.observe(t.time, price, price);
+    })?;
+
+    if !found {
+        // ローソク足は bid なので、売りのスプレッド分は含まない
+        source = EXCURSION_SOURCE_CANDLE;
+        let found = scan_candles(db, &pair, trade, |c| {
+            let (adverse, favorable) = if is_buy { (c.low, c.high) } else { (c.high, c.low) };
+            tracker.observe(c.time.max(trade.entry_time), adverse, favorable);
+        })?;
+        if !found {
+            return Ok(None);
+        }
+    }
+
+    let point = point_of(db, &trade.pair)?;
+    let yen = yen_per_price(trade);
+
+    let excursion = TradeExcursion {
+        trade_id,
+        source: source.to_string(),
+        mae_pips: (tracker.mae / point).round() as i32,
+        mfe_pips: (tracker.mfe / point).round() as i32,
+        mae_yen: (tracker.mae * yen).round() as i32,
+        mfe_yen: (tracker.mfe * yen).round() as i32,
+        mae_secs: tracker.mae_time - tracker.entry_time,
+        mfe_secs: tracker.mfe_time - tracker.entry_time,
+        computed_at: chrono::Utc::now().timestamp(),
+    };
+    excursions::upsert_excursion(db, &excursion)?;
+
+    Ok(Some(excursion))
+}
+
+/// 取引の MAE/MFE をまとめて計算する。recompute でなければ未計算の取引だけ
+pub fn compute_all(db: &DbState, recompute: bool) -> Result<ExcursionComputeSummary, String> {
+    let computed = if recompute {
+        HashSet::new()
+    } else {
+        excursions::get_computed_trade_ids(db)?
+    };
+
+    let mut summary = ExcursionComputeSummary::default();
+    for trade in trades::get_all_trades(db)? {
+        let id = trade.id.unwrap_or(0) as i64;
+        if computed.contains(&id) {
+            continue;
+        }
+
+        summary.trades += 1;
+        match compute_excursion(db, &trade)? {
+            Some(e) if e.source == EXCURSION_SOURCE_TICK => summary.from_ticks += 1,
+            Some(_) => summary.from_candles += 1,
+            None => summary.no_data += 1,
+        }
+    }
+
+    Ok(summary)
+}
+
+/// 保存済みの MAE/MFE。未計算か recompute なら計算する
+pub fn get_excursion(
+    db: &DbState,
+    trade_id: i64,
+    recompute: bool,
+) -> Result<Option<TradeExcursion>, String> {
+    if !recompute {
+        if let Some(e) = excursions::find_by_trade_id(db, trade_id)? {
+            return Ok(Some(e));
+        }
+    }
+
+    let trade = trades::find_by_id(db, trade_id)?.ok_or("トレードが見つかりません")?;
+    compute_excursion(db, &trade)
+}
+
+/// 保有期間のティックを順に渡す。1 件もなければ false
+fn scan_ticks(
+    db: &DbState,
+    pair: &str,
+    trade: &Trade,
+    mut f: impl FnMut(&crate::models::db::tick::Tick),
+) -> Result<bool, String> {
+    let to = trade.exit_time * 1000 + 999;
+    let mut from = trade.entry_time * 1000;
+    let mut found = false;
+
+    loop {
+        let page = ticks::find_ticks_page(db, pair, from, to, PAGE_SIZE)?;
+        for tick in &page {
+            f(tick);
+        }
+        found |= !page.is_empty();
+
+        match page.last() {
+            Some(last) if page.len() as i64 == PAGE_SIZE => from = last.time_msc + 1,
+            _ => break,
+        }
+    }
+
+    Ok(found)
+}
+
+/// 保有期間にかかる M1 のローソク足を順に渡す。1 本もなければ false
+fn scan_candles(
+    db: &DbState,
+    pair: &str,
+    trade: &Trade,
+    mut f: impl FnMut(&crate::models::db::candle::Candle),
+) -> Result<bool, String> {
+    // エントリーを含む足から決済を含む足まで
+    let mut from = trade.entry_time - trade.entry_time.rem_euclid(60);
+    let to = trade.exit_time + 1;
+    let mut found = false;
+
+    loop {
+        let page = candles::find_candles_page(db, pair, BASE_TIMEFRAME, from, to, PAGE_SIZE)?;
+        for candle in &page {
+            f(candle);
+        }
+        found |= !page.is_empty();
+
+        match page.last() {
+            Some(last) if page.len() as i64 == PAGE_SIZE => from = last.time + 1,
+            _ => break,
+        }
+    }
+
+    Ok(found)
+}
+
+/// 価格差 1 あたりの円。決済損益から逆算し、できなければ通貨量から求める
+fn yen_per_price(trade: &Trade) -> f64 {
+    let direction = if trade.side == "買" { 1.0 } else { -1.0 };
+    let diff = (trade.exit_rate - trade.entry_rate) * direction;
+    let profit = (trade.profit - trade.swap.unwrap_or(0)) as f64;
+    if diff.abs() > 1e-9 && profit / diff > 0.0 {
+        return profit / diff;
+    }
+
+    // trades の lot は取り込み元によらず LOT_UNITS 単位
+    trade.lot * LOT_UNITS
+}
@@ -0,0 +1,135 @@
+use crate::db::queries::{ticks, trades};
+use crate::db::DbState;
+use crate::models::db::trade::Trade;
+use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::service::execution::{
+    ExecutionGroup, ExecutionQuality, ExecutionStats, FillKind, FillQuality,
+};
+use crate::models::settings::analysis::{AnalysisConfig, ANALYSIS_CONFIG_KEY};
+use crate::service::settings;
+use crate::service::symbols::{market_symbol, point_of};
+use crate::utils::buckets;
+use crate::utils::time_utils::jst_hour;
+
+/// 約定レートを直前のティックと比べ、スリッページと支払ったスプレッドを集計する
+pub fn analyze(db: &DbState, filter: Option<TradeFilter>) -> Result<ExecutionQuality, String> {
+    let config = settings::load::<AnalysisConfig>(db, ANALYSIS_CONFIG_KEY)?;
+    let trades = match filter {
+        Some(f) => trades::get_by_filter(db, f)?,
+        None => trades::get_all_trades(db)?,
+    };
+
+    let mut result = ExecutionQuality::default();
+    let mut fills: Vec<(FillQuality, &Trade)> = Vec::new();
+    for trade in &trades {
+        for kind in [FillKind::Entry, FillKind::Exit] {
+            match evaluate(db, trade, kind, config.max_quote_age_secs)? {
+                Some(fill) => fills.push((fill, trade)),
+                None => result.no_quote += 1,
+            }
+        }
+    }
+
+    result.overall = group("all".to_string(), fills.iter());
+
+    for hour in 0..24 {
+        let targets = fills.iter().filter(|(f, _)| jst_hour(f.time) == hour);
+        result.by_hour.push(group(format!("{}時", hour), targets));
+    }
+
+    for (min, max) in buckets::ranges(&config.lot_buckets) {
+        let targets = fills
+            .iter()
+            .filter(|(_, t)| buckets::contains(min, max, t.lot));
+        result.by_lot.push(group(
+            buckets::name(min, max, |lot| lot.to_string()),
+            targets,
+        ));
+    }
+
+    let mut accounts: Vec<&str> = trades.iter().map(|t| t.account.as_str()).collect();
+    accounts.sort();
+    accounts.dedup();
+    for account in accounts {
+        let targets = fills.iter().filter(|(_, t)| t.account == account);
+        result.by_account.push(group(account.to_string(), targets));
+    }
+
+    result.fills = fills.into_iter().map(|(f, _)| f).collect();
+    Ok(result)
+}
+
+/// 約定直前のティックと比べる。max_age_secs 以内のティックがなければ None
+/// 新規の買い・決済の売りは ask、新規の売り・決済の買いは bid で約定する前提
+fn evaluate(
+    db: &DbState,
+    trade: &Trade,
+    kind: FillKind,
+    max_age_secs: i64,
+) -> Result<Option<FillQuality>, String> {
+    let (time, rate) = match kind {
+        FillKind::Entry => (trade.entry_time, trade.entry_rate),
+        FillKind::Exit => (trade.exit_time, trade.exit_rate),
+    };
+
+    // 約定時刻は秒単位なので、その秒の始まり以前のティックがなければ同じ秒の最初のティックを使う
+    let pair = market_symbol(&trade.pair);
+    let tick = match ticks::find_quote_at(db, &pair, (time - max_age_secs) * 1000, time * 1000)? {
+        Some(t) => t,
+        None => match ticks::find_first_quote(db, &pair, time * 1000 + 1, time * 1000 + 999)? {
+            Some(t) => t,
+            None => return Ok(None),
+        },
+    };
+
+    let is_buy = trade.side == "買";
+    let pays_ask = match kind {
+        FillKind::Entry => is_buy,
+        FillKind::Exit => !is_buy,
+    };
+    let quote = if pays_ask { tick.ask } else { tick.bid };
+    // ask で買うなら高いほど、bid で売るなら安いほど不利
+    let slippage = if pays_ask { rate - quote } else { quote - rate };
+
+    let point = point_of(db, &trade.pair)?;
+    Ok(Some(FillQuality {
+        trade_id: trade.id.unwrap_or(0) as i64,
+        kind,
+        time,
+        rate,
+        quote,
+        quote_time_msc: tick.time_msc,
+        slippage_pips: (slippage / point).round() as i32,
+        spread_pips: ((tick.ask - tick.bid) / point).round() as i32,
+    }))
+}
+
+fn group<'a>(
+    name: String,
+    fills: impl Iterator<Item = &'a (FillQuality, &'a Trade)>,
+) -> ExecutionGroup {
+    let (entries, exits): (Vec<&FillQuality>, Vec<&FillQuality>) = fills
+        .map(|(f, _)| f)
+        .partition(|f| f.kind == FillKind::Entry);
+
+    ExecutionGroup {
+        name,
+        entry: stats(&entries),
+        exit: stats(&exits),
+    }
+}
+
+fn stats(fills: &[&FillQuality]) -> ExecutionStats {
+    if fills.is_empty() {
+        return ExecutionStats::default();
+    }
+
+    let n = fills.len() as f64;
+    ExecutionStats {
+        count: fills.len() as i32,
+        avg_slippage_pips: fills.iter().map(|f| f.slippage_pips as f64).sum::<f64>() / n,
+        avg_spread_pips: fills.iter().map(|f| f.spread_pips as f64).sum::<f64>() / n,
+        worst_slippage_pips: fills.iter().map(|f| f.slippage_pips).max().unwrap_or(0),
+        improved_rate: fills.iter().filter(|f| f.slippage_pips < 0).count() as f64 / n,
+    }
+}
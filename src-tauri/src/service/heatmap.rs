@@ -0,0 +1,137 @@
+use crate::db::queries::trades;
+use crate::db::DbState;
+use crate::models::db::trade::Trade;
+use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::service::heatmap::{HeatmapCell, TradeHeatmap};
+use crate::utils::time_utils::{jst_hour, jst_weekday};
+use chrono::{TimeZone, Timelike};
+use chrono_tz::Tz;
+
+const WEEKDAYS: [&str; 7] = ["月", "火", "水", "木", "金", "土", "日"];
+
+// 各市場の現地時刻での取引時間 [open, close)。夏時間は chrono-tz に任せる
+const SESSIONS: [(&str, Tz, u32, u32); 3] = [
+    ("東京", chrono_tz::Asia::Tokyo, 9, 18),
+    ("ロンドン", chrono_tz::Europe::London, 8, 17),
+    ("ニューヨーク", chrono_tz::America::New_York, 8, 17),
+];
+const OFF_SESSION: &str = "その他";
+
+/// エントリー時刻（JST）の時間帯・曜日・市場ごとに成績をまとめる
+pub fn analyze(db: &DbState, filter: Option<TradeFilter>) -> Result<TradeHeatmap, String> {
+    let trades = match filter {
+        Some(f) => trades::get_by_filter(db, f)?,
+        None => trades::get_all_trades(db)?,
+    };
+
+    let session_count = SESSIONS.len() + 1;
+    let mut cells = Cells {
+        by_hour: vec![Vec::new(); 24],
+        by_weekday: vec![Vec::new(); 7],
+        by_session: vec![Vec::new(); session_count],
+        weekday_hour: vec![vec![Vec::new(); 24]; 7],
+        weekday_session: vec![vec![Vec::new(); session_count]; 7],
+    };
+    let mut result = TradeHeatmap {
+        hours: (0..24).map(|h| format!("{}時", h)).collect(),
+        weekdays: WEEKDAYS.iter().map(|d| d.to_string()).collect(),
+        sessions: SESSIONS
+            .iter()
+            .map(|(name, ..)| name.to_string())
+            .chain(std::iter::once(OFF_SESSION.to_string()))
+            .collect(),
+        ..Default::default()
+    };
+
+    for t in &trades {
+        let hour = jst_hour(t.entry_time) as usize;
+        let weekday = jst_weekday(t.entry_time) as usize;
+
+        cells.by_hour[hour].push(t);
+        cells.by_weekday[weekday].push(t);
+        cells.weekday_hour[weekday][hour].push(t);
+        for session in sessions_of(t.entry_time) {
+            cells.by_session[session].push(t);
+            cells.weekday_session[weekday][session].push(t);
+        }
+    }
+
+    result.total = cell(trades.iter());
+    result.by_hour = cells
+        .by_hour
+        .iter()
+        .map(|c| cell(c.iter().copied()))
+        .collect();
+    result.by_weekday = cells
+        .by_weekday
+        .iter()
+        .map(|c| cell(c.iter().copied()))
+        .collect();
+    result.by_session = cells
+        .by_session
+        .iter()
+        .map(|c| cell(c.iter().copied()))
+        .collect();
+    result.weekday_hour = cells
+        .weekday_hour
+        .iter()
+        .map(|row| row.iter().map(|c| cell(c.iter().copied())).collect())
+        .collect();
+    result.weekday_session = cells
+        .weekday_session
+        .iter()
+        .map(|row| row.iter().map(|c| cell(c.iter().copied())).collect())
+        .collect();
+
+    Ok(result)
+}
+
+// マスごとのトレード
+struct Cells<'a> {
+    by_hour: Vec<Vec<&'a Trade>>,
+    by_weekday: Vec<Vec<&'a Trade>>,
+    by_session: Vec<Vec<&'a Trade>>,
+    weekday_hour: Vec<Vec<Vec<&'a Trade>>>,
+    weekday_session: Vec<Vec<Vec<&'a Trade>>>,
+}
+
+/// その時刻に開いている市場の添字。どこも開いていなければ「その他」
+fn sessions_of(unix: i64) -> Vec<usize> {
+    let open: Vec<usize> = SESSIONS
+        .iter()
+        .enumerate()
+        .filter(|(_, (_, tz, open, close))| {
+            tz.timestamp_opt(unix, 0)
+                .single()
+                .is_some_and(|dt| dt.hour() >= *open && dt.hour() < *close)
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    if open.is_empty() {
+        vec![SESSIONS.len()]
+    } else {
+        open
+    }
+}
+
+fn cell<'a>(trades: impl Iterator<Item = &'a Trade>) -> HeatmapCell {
+    let mut c = HeatmapCell::default();
+    for t in trades {
+        c.count += 1;
+        c.profit += t.profit;
+        c.profit_pips += t.profit_pips;
+        if t.profit > 0 {
+            c.wins += 1;
+        } else if t.profit < 0 {
+            c.losses += 1;
+        }
+    }
+    if c.count > 0 {
+        let n = c.count as f64;
+        c.win_rate = c.wins as f64 / n;
+        c.avg_profit = c.profit as f64 / n;
+        c.avg_profit_pips = c.profit_pips as f64 / n;
+    }
+    c
+}
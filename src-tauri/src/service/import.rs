@@ -2,7 +2,12 @@ use crate::db::queries::{candles, trades};
 use crate::db::DbState;
 use crate::models::db::candle::Candle;
 use crate::models::db::record::Record;
+use crate::models::db::tick::Tick;
 use crate::models::db::trade::Trade;
+use crate::models::service::import_summary::{AutoMerge, ImportSummary};
+use crate::models::settings::market_data::{BASE_TIMEFRAME, DEFAULT_SYMBOL};
+use crate::models::settings::merge::{AutoMergeRule, MergeConfig, MERGE_CONFIG_KEY};
+use crate::service::symbols::point_of;
 use crate::utils::time_utils::jst_str_to_unix;
 use chrono::{NaiveDateTime, TimeZone};
 use chrono_tz::Europe::Helsinki;
@@ -11,7 +16,10 @@ use encoding_rs::SHIFT_JIS;
 use encoding_rs_io::DecodeReaderBytesBuilder;
 use std::fs::File;
 
-pub fn import_csv_to_db(db: &DbState, csv_paths: Vec<String>) -> Result<(), String> {
+// アプリの 1 ロットの通貨量（DMM・GMO の CSV と同じ 1 万通貨）
+pub const LOT_UNITS: f64 = 10_000.0;
+
+pub fn import_csv_to_db(db: &DbState, csv_paths: Vec<String>) -> Result<ImportSummary, String> {
     if csv_paths.is_empty() {
         return Err("CSVファイルが選択されていません".to_string());
     }
@@ -118,13 +126,34 @@ pub fn import_csv_to_db(db: &DbState, csv_paths: Vec<String>) -> Result<(), Stri
     }
 
     all_records.sort_by_key(|r| r.order_time);
-    insert_trade(db, all_records)?;
+    insert_trade(db, all_records)
+}
 
-    Ok(())
+fn insert_trade(db: &DbState, records: Vec<Record>) -> Result<ImportSummary, String> {
+    let rule = load_auto_merge_rule(db)?;
+
+    let mut summary = ImportSummary::default();
+    for (trade, _) in build_trades(db, records, "DMM")? {
+        let (_, inserted, merged) = save_trade(db, trade, &rule)?;
+        // 取り込み済みの取引は数えない
+        if inserted {
+            summary.trades += 1;
+        }
+        summary.auto_merges.extend(merged);
+    }
+
+    Ok(summary)
 }
 
-fn insert_trade(db: &DbState, records: Vec<Record>) -> Result<(), String> {
-    let mut positions = Vec::new();
+/// 新規・決済の約定を突き合わせて取引を組み立てる
+/// Record の lot は LOT_UNITS 単位、profit はスワップを含む円建て
+/// 戻り値は取引と、その決済約定の MT5 チケット（CSV では None）
+pub fn build_trades(
+    db: &DbState,
+    records: Vec<Record>,
+    account: &str,
+) -> Result<Vec<(Trade, Option<i64>)>, String> {
+    let mut positions: Vec<Record> = Vec::new();
     let mut trades =  Vec::new();
     for record in records{
         match record.trade_type.as_str() {
@@ -141,16 +170,20 @@ fn insert_trade(db: &DbState, records: Vec<Record>) -> Result<(), String> {
                 let exit_rate = record.rate;
 
                 let expected_entry_rate =
-                    exit_rate + (profit - swap) as f64 / (close_lot * 10000.0) * direction;
+                    exit_rate + (profit - swap) as f64 / (close_lot * LOT_UNITS) * direction;
 
                 if let Some((idx, _pos)) = positions
                     .iter()
                     .cloned()
                     .enumerate()
-                    .find(|(_, pos)| {
-                        pos.pair == record.pair
-                            && pos.side != record.side
-                            && (pos.rate - expected_entry_rate).abs() < 0.00001
+                    .find(|(_, pos)| match record.position_id {
+                        // MT5 はポジションIDで確定できる
+                        Some(id) => pos.position_id == Some(id),
+                        None => {
+                            pos.pair == record.pair
+                                && pos.side != record.side
+                                && (pos.rate - expected_entry_rate).abs() < 0.00001
+                        }
                     })
                 {
                     let mut pos = positions.remove(idx);
@@ -161,8 +194,9 @@ fn insert_trade(db: &DbState, records: Vec<Record>) -> Result<(), String> {
 
                     let matched_lot = close_lot.min(position_lot);
 
+                    let point = point_of(db, &record.pair)?;
                     let profit_pips =
-                        ((entry_rate - exit_rate) * 1000.0 * direction).round() as i32;
+                        ((entry_rate - exit_rate) / point * direction).round() as i32;
 
                     let trade = Trade {
                         pair: record.pair.clone(),
@@ -175,14 +209,15 @@ fn insert_trade(db: &DbState, records: Vec<Record>) -> Result<(), String> {
                         profit,
                         profit_pips,
                         swap: Some(swap),
-                        account: "DMM".to_string(),
+                        account: account.to_string(),
                         ..Default::default()
                     };
 
-                    trades.push(trade);
+                    trades.push((trade, record.ticket));
 
                     if position_lot > matched_lot {
-                        pos.lot = position_lot - matched_lot;
+                        // 0.3 - 0.1 のような誤差が残らないよう丸める
+                        pos.lot = ((position_lot - matched_lot) * 1e8).round() / 1e8;
                         positions.push(pos);
                     }
                 } else {
@@ -197,16 +232,57 @@ fn insert_trade(db: &DbState, records: Vec<Record>) -> Result<(), String> {
         }
     }
 
-    for t in trades {
-        trades::insert_trade(db, t.clone())?;
-        let similar = trades::find_similar_trades(db, t.clone())?;
-        if similar.len() >= 2 {
-            let ids: Vec<i64> = similar.iter().map(|s| s.id.unwrap_or(0) as i64).collect();
-            crate::service::trades::merge_trades(db, ids)?;
-        }
+    Ok(trades)
+}
+
+pub fn load_auto_merge_rule(db: &DbState) -> Result<AutoMergeRule, String> {
+    Ok(crate::service::settings::load::<MergeConfig>(db, MERGE_CONFIG_KEY)?.auto_merge)
+}
+
+/// 取引を保存し、自動マージの条件に合う同時刻の分割約定があればまとめる
+/// 戻り値は取引の ID、新しく追加したか、まとめた場合はその内容
+/// 同じ内容の取引がすでにあれば既存の ID を返し、まとめない
+pub fn save_trade(
+    db: &DbState,
+    trade: Trade,
+    rule: &AutoMergeRule,
+) -> Result<(i64, bool, Option<AutoMerge>), String> {
+    let (id, inserted) = trades::insert_trade(db, trade.clone())?;
+    if !inserted || !rule.applies_to(&trade.account) {
+        return Ok((id, inserted, None));
     }
 
-    Ok(())
+    let similar: Vec<Trade> = trades::find_similar_trades(db, trade.clone(), rule.window_secs)?
+        .into_iter()
+        .filter(|t| !rule.require_same_entry_rate || (t.entry_rate - trade.entry_rate).abs() < 1e-8)
+        .collect();
+    if similar.len() < 2 {
+        return Ok((id, true, None));
+    }
+
+    // 意図的な積み増しまでまとめないよう、ロットが大きくなるものは残す
+    let total_lot: f64 = similar.iter().map(|t| t.lot).sum();
+    if rule.max_lot.is_some_and(|max| total_lot > max + 1e-8) {
+        return Ok((id, true, None));
+    }
+
+    let ids: Vec<i64> = similar.iter().map(|s| s.id.unwrap_or(0) as i64).collect();
+    let merged_id = crate::service::trades::merge_trades(db, ids.clone(), None)?;
+    let merged = trades::find_by_id(db, merged_id)?.unwrap_or_default();
+
+    Ok((
+        id,
+        true,
+        Some(AutoMerge {
+            merged_id,
+            source_ids: ids,
+            pair: merged.pair,
+            side: merged.side,
+            lot: merged.lot,
+            entry_time: merged.entry_time,
+            exit_time: merged.exit_time,
+        }),
+    ))
 }
 
 // DMM用の処理
@@ -301,41 +377,128 @@ fn parse_i32_from_csv(s: &str) -> Option<i32> {
 }
 
 pub fn import_candle_to_db(db: &DbState, csv_path: &str) -> Result<(), String> {
+    let candles = read_mt5_candle_csv(csv_path, DEFAULT_SYMBOL, i64::MIN, usize::MAX)?;
+    candles::insert_candles_bulk(db, BASE_TIMEFRAME, &candles)?;
+
+    Ok(())
+}
+
+/// MT5 でエクスポートしたローソク足 CSV（タブ区切り）を読み込む
+/// since より後の足を limit 件まで返す。ファイルは時刻順なので limit 件で読むのをやめる
+pub fn read_mt5_candle_csv(
+    csv_path: &str,
+    pair: &str,
+    since: i64,
+    limit: usize,
+) -> Result<Vec<Candle>, String> {
     let mut rdr = ReaderBuilder::new()
         .delimiter(b'\t')
         .from_reader(File::open(csv_path).map_err(|e| e.to_string())?);
 
+    let mut candles = Vec::new();
     for result in rdr.records() {
+        if candles.len() >= limit {
+            break;
+        }
         let record = result.map_err(|e| e.to_string())?;
-        // "2025.08.01" → "2025-08-01"
-        let date = record[0].replace(".", "-");
-        let time = &record[1];
-        let datetime_str = format!("{} {}", date, time);
-
-        // NaiveDateTimeとしてパース（まだタイムゾーンなし）
-        let naive_dt = NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S")
-            .map_err(|e| e.to_string())?;
-
-        // Europe/Helsinki（DST対応）としてローカル→UTC変換
-        let local_dt = Helsinki
-            .from_local_datetime(&naive_dt)
-            .single()
-            .ok_or("DST重複または欠落エラー")?;
-        let unix_time = local_dt.timestamp();
-
-        let candle = Candle {
-            pair: "JPY/USD".to_string(),
+        let unix_time = broker_str_to_unix(&record[0], &record[1])?;
+        if unix_time <= since {
+            continue;
+        }
+
+        candles.push(Candle {
+            pair: pair.to_string(),
             time: unix_time,
-            open: record[2].parse::<f64>().unwrap(),
-            high: record[3].parse::<f64>().unwrap(),
-            low: record[4].parse::<f64>().unwrap(),
-            close: record[5].parse::<f64>().unwrap(),
-            tickvol: record[6].parse::<i64>().unwrap(),
-            vol: record[7].parse::<i64>().unwrap(),
-            spread: record[8].parse::<i64>().unwrap(),
+            open: parse_csv_field(&record[2])?,
+            high: parse_csv_field(&record[3])?,
+            low: parse_csv_field(&record[4])?,
+            close: parse_csv_field(&record[5])?,
+            tickvol: parse_csv_field(&record[6])?,
+            vol: parse_csv_field(&record[7])?,
+            spread: parse_csv_field(&record[8])?,
+        });
+    }
+
+    Ok(candles)
+}
+
+/// MT5 でエクスポートしたティック CSV（タブ区切り）を読み込む
+/// 片側しか更新されていない行は BID/ASK が空なので直前の値を引き継ぐ
+/// since 以降のティックを limit 件まで返す。日付・時刻が欠けた行は飛ばす
+pub fn read_mt5_tick_csv(
+    csv_path: &str,
+    pair: &str,
+    since: i64,
+    limit: usize,
+) -> Result<Vec<Tick>, String> {
+    let mut rdr = ReaderBuilder::new()
+        .delimiter(b'\t')
+        .flexible(true)
+        .from_reader(File::open(csv_path).map_err(|e| e.to_string())?);
+
+    let mut ticks = Vec::new();
+    let mut bid = 0.0;
+    let mut ask = 0.0;
+    for result in rdr.records() {
+        if ticks.len() >= limit {
+            break;
+        }
+        let record = result.map_err(|e| e.to_string())?;
+        let (date, time) = match (record.get(0), record.get(1)) {
+            (Some(d), Some(t)) if !d.is_empty() && !t.is_empty() => (d, t),
+            _ => continue,
+        };
+
+        // "00:00:00.123" → 秒とミリ秒に分ける
+        let (time, msc) = match time.split_once('.') {
+            Some((t, ms)) => (t, ms.parse::<i64>().unwrap_or(0)),
+            None => (time, 0),
         };
-        candles::insert_candle(db, candle)?;
+        let unix_time = broker_str_to_unix(date, time)?;
+
+        if let Some(v) = record.get(2).filter(|v| !v.is_empty()) {
+            bid = parse_csv_field(v)?;
+        }
+        if let Some(v) = record.get(3).filter(|v| !v.is_empty()) {
+            ask = parse_csv_field(v)?;
+        }
+        // 前のティックの BID/ASK は引き継ぐので、since より前の行も読む
+        if bid == 0.0 || ask == 0.0 || unix_time < since {
+            continue;
+        }
+
+        ticks.push(Tick {
+            pair: pair.to_string(),
+            time: unix_time,
+            time_msc: unix_time * 1000 + msc,
+            bid,
+            ask,
+        });
     }
 
-    Ok(())
+    Ok(ticks)
+}
+
+/// MT5 のサーバー時刻 "2025.08.01" "00:00:00" → UNIX time
+fn broker_str_to_unix(date: &str, time: &str) -> Result<i64, String> {
+    // "2025.08.01" → "2025-08-01"
+    let datetime_str = format!("{} {}", date.replace(".", "-"), time);
+
+    // NaiveDateTimeとしてパース（まだタイムゾーンなし）
+    let naive_dt = NaiveDateTime::parse_from_str(&datetime_str, "%Y-%m-%d %H:%M:%S")
+        .map_err(|e| e.to_string())?;
+
+    // Europe/Helsinki（DST対応）としてローカル→UTC変換
+    let local_dt = Helsinki
+        .from_local_datetime(&naive_dt)
+        .single()
+        .ok_or("DST重複または欠落エラー")?;
+
+    Ok(local_dt.timestamp())
+}
+
+fn parse_csv_field<T: std::str::FromStr>(s: &str) -> Result<T, String> {
+    s.trim()
+        .parse::<T>()
+        .map_err(|_| format!("数値に変換できません: {}", s))
 }
@@ -2,6 +2,7 @@ use crate::db::queries::{trade_label, trades};
 use crate::db::DbState;
 use crate::models::db::label::Label;
 use crate::models::service::label_summary::LabelSummary;
+use crate::models::service::trade_metrics::TradeMetrics;
 
 pub fn insert_label(db: &DbState, name: &str) -> Result<(), String> {
     let conn = db.conn.lock().unwrap();
@@ -73,6 +74,7 @@ pub fn fetch_all_label_with_trade(state: &DbState) -> Result<Vec<LabelSummary>,
             id: id,
             name: name,
             trades: trades.clone(),
+            metrics: TradeMetrics::from_trades(&trades),
             ..Default::default()
         };
         for t in trades {
@@ -95,3 +97,14 @@ pub fn fetch_all_label_with_trade(state: &DbState) -> Result<Vec<LabelSummary>,
 
     Ok(label_with_trades)
 }
+
+/// トレード一覧を含まないラベルごとの集計
+pub fn fetch_all_label_summaries(state: &DbState) -> Result<Vec<LabelSummary>, String> {
+    Ok(fetch_all_label_with_trade(state)?
+        .into_iter()
+        .map(|s| LabelSummary {
+            trades: Vec::new(),
+            ..s
+        })
+        .collect())
+}
@@ -1,8 +1,24 @@
+pub mod audit;
+pub mod campaigns;
 pub mod candles;
+pub mod deals;
+pub mod equity;
+pub mod excursions;
+pub mod execution;
+pub mod heatmap;
 pub mod import;
 pub mod labels;
 pub mod meta;
+pub mod positions;
+pub mod post_exit;
 pub mod records;
+pub mod scheduler;
+pub mod settings;
+pub mod stream;
+pub mod symbols;
+pub mod sync;
+pub mod tick_stream;
+pub mod trade_context;
 pub mod trades;
 pub mod ticks;
 pub mod daily_memo;
@@ -0,0 +1,194 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::queries::{deals, symbols, ticks};
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::db::deal::{
+    DEAL_ENTRY_IN, DEAL_ENTRY_INOUT, DEAL_ENTRY_OUT, DEAL_ENTRY_OUT_BY, DEAL_TYPE_BUY,
+};
+use crate::models::service::position::{
+    LivePosition, OpenPosition, PositionSnapshot, PositionSource,
+};
+use crate::service::import::LOT_UNITS;
+use crate::service::symbols::point_of;
+
+// 保有ポジションを通知するイベント名
+pub const POSITIONS_EVENT: &str = "open-positions";
+
+// 評価し直す間隔
+const POLL_SECS: u64 = 2;
+
+// 銘柄カタログがないときの 1 ロットあたりの通貨量（MT5 の FX 標準）
+const DEFAULT_CONTRACT_SIZE: f64 = 100_000.0;
+
+/// 保有ポジションの監視（画面を開いている間だけ動かす）
+#[derive(Default)]
+pub struct PositionMonitorState {
+    enabled: AtomicBool,
+    latest: Mutex<Option<PositionSnapshot>>,
+}
+
+impl PositionMonitorState {
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn latest(&self) -> Result<Option<PositionSnapshot>, String> {
+        let latest = self.latest.lock().map_err(|e| e.to_string())?;
+        Ok(latest.clone())
+    }
+
+    fn store(&self, snapshot: PositionSnapshot) -> Result<(), String> {
+        *self.latest.lock().map_err(|e| e.to_string())? = Some(snapshot);
+        Ok(())
+    }
+}
+
+/// アプリ終了まで保有ポジションを評価し続ける
+pub async fn run_position_monitor(app: AppHandle) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(POLL_SECS)).await;
+
+        let monitor = app.state::<PositionMonitorState>();
+        if !monitor.enabled.load(Ordering::SeqCst) {
+            continue;
+        }
+
+        let db = app.state::<DbState>();
+        let market = app.state::<MarketDataState>();
+        match get_snapshot(&db, &market).await {
+            Ok(snapshot) => {
+                let _ = monitor.store(snapshot.clone());
+                let _ = app.emit(POSITIONS_EVENT, snapshot);
+            }
+            Err(e) => eprintln!("❌ Position monitor failed: {}", e),
+        }
+    }
+}
+
+/// 保有ポジションを最新ティックで評価する
+/// ブリッジから取れなければ取り込み済みの約定から復元する
+pub async fn get_snapshot(
+    db: &DbState,
+    market: &MarketDataState,
+) -> Result<PositionSnapshot, String> {
+    let provider = market.provider()?;
+    let (source, positions, error) = match provider.fetch_positions().await {
+        Ok(positions) => (PositionSource::Bridge, positions, None),
+        Err(e) => (PositionSource::Ledger, positions_from_deals(db)?, Some(e)),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    let mut live = Vec::new();
+    for p in positions {
+        live.push(evaluate(db, p, now)?);
+    }
+    // 通貨の違う損益は足さない
+    let mut total_unrealized: BTreeMap<String, f64> = BTreeMap::new();
+    for p in &live {
+        if let Some(unrealized) = p.unrealized {
+            *total_unrealized.entry(p.currency.clone()).or_insert(0.0) += unrealized;
+        }
+    }
+
+    Ok(PositionSnapshot {
+        source,
+        positions: live,
+        total_unrealized,
+        updated_at: now,
+        error,
+    })
+}
+
+fn evaluate(db: &DbState, position: OpenPosition, now: i64) -> Result<LivePosition, String> {
+    let is_buy = position.position_type == DEAL_TYPE_BUY;
+    let direction = if is_buy { 1.0 } else { -1.0 };
+
+    // 買いは bid、売りは ask で決済する前提で評価する
+    let tick = ticks::find_latest(db, &position.symbol)?;
+    let current_rate = tick.as_ref().map(|t| if is_buy { t.bid } else { t.ask });
+    let diff = current_rate.map(|rate| (rate - position.price_open) * direction);
+    let spec = symbols::find_by_name(db, &position.symbol)?;
+    let contract_size = spec
+        .as_ref()
+        .map(|s| s.contract_size)
+        .filter(|&size| size > 0.0)
+        .unwrap_or(DEFAULT_CONTRACT_SIZE);
+    let currency = spec
+        .map(|s| s.currency_profit)
+        .filter(|c| !c.is_empty())
+        .unwrap_or_else(|| quote_currency(&position.symbol));
+    let point = point_of(db, &position.symbol)?;
+
+    Ok(LivePosition {
+        ticket: position.ticket,
+        pair: position.symbol,
+        side: if is_buy { "買" } else { "売" }.to_string(),
+        // trades と同じアプリのロット（LOT_UNITS 通貨）に換算する
+        lot: (position.volume * contract_size / LOT_UNITS * 1e8).round() / 1e8,
+        entry_rate: position.price_open,
+        entry_time: position.time,
+        current_rate,
+        price_time: tick.map(|t| t.time),
+        unrealized: diff.map(|d| d * position.volume * contract_size),
+        currency,
+        pips: diff.map(|d| (d / point).round() as i32),
+        swap: position.swap,
+        holding_secs: (now - position.time).max(0),
+    })
+}
+
+/// カタログにない銘柄は名前の後ろ 3 文字を決済通貨とみなす（"USDJPY" → "JPY"）
+fn quote_currency(symbol: &str) -> String {
+    let letters: String = symbol.chars().filter(|c| c.is_ascii_alphabetic()).collect();
+    if letters.len() == 6 {
+        letters[3..].to_uppercase()
+    } else {
+        String::new()
+    }
+}
+
+/// 約定履歴から決済されていない建玉を集計する
+fn positions_from_deals(db: &DbState) -> Result<Vec<OpenPosition>, String> {
+    let mut open: BTreeMap<i64, OpenPosition> = BTreeMap::new();
+
+    for deal in deals::get_all_deals(db)? {
+        let position = open.entry(deal.position_id).or_insert_with(|| OpenPosition {
+            ticket: deal.position_id,
+            symbol: deal.symbol.clone(),
+            position_type: deal.deal_type,
+            time: deal.time,
+            ..Default::default()
+        });
+
+        match deal.entry {
+            DEAL_ENTRY_IN => {
+                // 追加の建玉は平均建値にする
+                let volume = position.volume + deal.volume;
+                position.price_open =
+                    (position.price_open * position.volume + deal.price * deal.volume) / volume;
+                position.volume = volume;
+            }
+            DEAL_ENTRY_OUT | DEAL_ENTRY_OUT_BY => {
+                position.volume = ((position.volume - deal.volume) * 1e8).round() / 1e8;
+            }
+            DEAL_ENTRY_INOUT => {
+                // ドテンは残りを反対方向の新しい建玉とみなす
+                position.volume = deal.volume - position.volume;
+                position.position_type = deal.deal_type;
+                position.price_open = deal.price;
+                position.time = deal.time;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(open
+        .into_values()
+        .filter(|p| p.volume > 1e-8)
+        .collect())
+}
@@ -0,0 +1,193 @@
+use crate::db::queries::{candles, trade_label, trades};
+use crate::db::DbState;
+use crate::models::db::trade::Trade;
+use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::service::post_exit::{
+    PostExitAnalysis, PostExitGroup, PostExitMove, PostExitStats, PostExitTrade,
+};
+use crate::models::settings::analysis::{AnalysisConfig, ANALYSIS_CONFIG_KEY};
+use crate::models::settings::market_data::{BASE_TIMEFRAME, MAX_BASE_CANDLES};
+use crate::service::settings;
+use crate::service::symbols::{market_symbol, point_of};
+use crate::utils::buckets;
+
+// 期間の終わりまでにこれ以上足が欠けていたら、その期間は測らない（週末・データの末尾など）
+const MAX_GAP_SECS: i64 = 5 * 60;
+
+/// 決済後の値動きを取引ごとに測り、ラベル・保有時間ごとに平均する
+/// windows を省略したら設定の期間を使う
+pub fn analyze(
+    db: &DbState,
+    filter: Option<TradeFilter>,
+    windows: Option<Vec<i64>>,
+) -> Result<PostExitAnalysis, String> {
+    let config = settings::load::<AnalysisConfig>(db, ANALYSIS_CONFIG_KEY)?;
+    let mut windows = windows.unwrap_or(config.post_exit_windows_secs);
+    windows.retain(|w| *w > 0);
+    windows.sort();
+    windows.dedup();
+    if windows.is_empty() {
+        return Err("決済後の期間を指定してください".into());
+    }
+
+    let trades = match filter {
+        Some(f) => trades::get_by_filter(db, f)?,
+        None => trades::get_all_trades(db)?,
+    };
+
+    let mut measured: Vec<(Trade, PostExitTrade, Vec<i32>)> = Vec::new();
+    for trade in trades {
+        let trade_id = trade.id.unwrap_or(0);
+        let moves = measure(db, &trade, &windows)?;
+        let label_ids = trade_label::find_by_trade_id(db, trade_id)?;
+        measured.push((
+            trade,
+            PostExitTrade {
+                trade_id: trade_id as i64,
+                moves,
+            },
+            label_ids,
+        ));
+    }
+
+    let overall = stats(&windows, measured.iter().map(|(_, m, _)| m));
+
+    let mut by_label = Vec::new();
+    for label in crate::service::labels::fetch_all_labels(db)? {
+        let label_id = label.id.unwrap_or(0);
+        let targets = measured
+            .iter()
+            .filter(|(_, _, labels)| labels.contains(&label_id))
+            .map(|(_, m, _)| m);
+        by_label.push(PostExitGroup {
+            name: label.name,
+            label_id: label.id,
+            stats: stats(&windows, targets),
+            ..Default::default()
+        });
+    }
+
+    let mut by_holding = Vec::new();
+    for (min, max) in buckets::ranges(&config.holding_buckets_secs) {
+        let targets = measured
+            .iter()
+            .filter(|(t, _, _)| buckets::contains(min, max, t.exit_time - t.entry_time))
+            .map(|(_, m, _)| m);
+        by_holding.push(PostExitGroup {
+            name: buckets::name(min, max, format_secs),
+            min_holding_secs: min,
+            max_holding_secs: max,
+            stats: stats(&windows, targets),
+            ..Default::default()
+        });
+    }
+
+    Ok(PostExitAnalysis {
+        windows_secs: windows,
+        overall,
+        by_label,
+        by_holding,
+        trades: measured.into_iter().map(|(_, m, _)| m).collect(),
+    })
+}
+
+/// 決済後の各期間の値動きを M1 のローソク足から測る
+fn measure(db: &DbState, trade: &Trade, windows: &[i64]) -> Result<Vec<PostExitMove>, String> {
+    let max_window = windows.iter().copied().max().unwrap_or(0);
+
+    // 決済前の値を含まないよう、決済後に始まる足から
+    let from = trade.exit_time + (60 - trade.exit_time.rem_euclid(60)) % 60;
+    let candles = candles::find_candles_page(
+        db,
+        &market_symbol(&trade.pair),
+        BASE_TIMEFRAME,
+        from,
+        trade.exit_time + max_window,
+        MAX_BASE_CANDLES,
+    )?;
+
+    let point = point_of(db, &trade.pair)?;
+    let is_buy = trade.side == "買";
+    let direction = if is_buy { 1.0 } else { -1.0 };
+    let to_pips = |diff: f64| (diff * direction / point).round() as i32;
+
+    let mut moves = Vec::new();
+    for &window in windows {
+        let in_window: Vec<_> = candles
+            .iter()
+            .filter(|c| c.time < trade.exit_time + window)
+            .collect();
+        let last = match in_window.last() {
+            Some(c) => c,
+            None => continue,
+        };
+        // M1 の最後の足が期間の終わり近くまで届いていなければ、途中までの値動きになる
+        if last.time + 60 < trade.exit_time + window - MAX_GAP_SECS {
+            continue;
+        }
+
+        // 買いなら高値が伸び、安値が戻り。売りは逆
+        let favorable = in_window
+            .iter()
+            .map(|c| to_pips((if is_buy { c.high } else { c.low }) - trade.exit_rate))
+            .max()
+            .unwrap_or(0);
+        let adverse = in_window
+            .iter()
+            .map(|c| to_pips(trade.exit_rate - if is_buy { c.low } else { c.high }))
+            .max()
+            .unwrap_or(0);
+
+        moves.push(PostExitMove {
+            window_secs: window,
+            favorable_pips: favorable.max(0),
+            adverse_pips: adverse.max(0),
+            net_pips: to_pips(last.close - trade.exit_rate),
+        });
+    }
+
+    Ok(moves)
+}
+
+fn stats<'a>(windows: &[i64], trades: impl Iterator<Item = &'a PostExitTrade>) -> Vec<PostExitStats> {
+    let trades: Vec<&PostExitTrade> = trades.collect();
+
+    windows
+        .iter()
+        .map(|&window| {
+            let moves: Vec<&PostExitMove> = trades
+                .iter()
+                .filter_map(|t| t.moves.iter().find(|m| m.window_secs == window))
+                .collect();
+            if moves.is_empty() {
+                return PostExitStats {
+                    window_secs: window,
+                    ..Default::default()
+                };
+            }
+
+            let n = moves.len() as f64;
+            PostExitStats {
+                window_secs: window,
+                count: moves.len() as i32,
+                avg_favorable_pips: moves.iter().map(|m| m.favorable_pips as f64).sum::<f64>() / n,
+                avg_adverse_pips: moves.iter().map(|m| m.adverse_pips as f64).sum::<f64>() / n,
+                avg_net_pips: moves.iter().map(|m| m.net_pips as f64).sum::<f64>() / n,
+                continued_rate: moves.iter().filter(|m| m.net_pips > 0).count() as f64 / n,
+            }
+        })
+        .collect()
+}
+
+/// 300 → "5m" のような期間の表示
+fn format_secs(secs: i64) -> String {
+    if secs % 86400 == 0 {
+        format!("{}d", secs / 86400)
+    } else if secs % 3600 == 0 {
+        format!("{}h", secs / 3600)
+    } else if secs % 60 == 0 {
+        format!("{}m", secs / 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
@@ -2,7 +2,9 @@ use crate::db::queries::{records, trades};
 use crate::db::DbState;
 use crate::models::db::record::Record;
 use crate::models::db::trade::Trade;
+use crate::models::filter::trade_filter::TradeFilter;
 use crate::models::service::daily_summary::DailySummary;
+use crate::models::service::period_summary::{PeriodChange, PeriodSummary, SummaryPeriod};
 use crate::models::service::trade_summary::TradeSummary;
 use crate::utils::time_utils::get_business_date_from_unix;
 use std::collections::HashMap;
@@ -46,6 +48,82 @@ pub fn fetch_daily_records(db: &DbState) -> Result<Vec<DailySummary>, String> {
     Ok(summary)
 }
 
+/// トレード一覧を含まない日ごとの集計（カレンダー用）
+pub fn fetch_daily_summaries(db: &DbState) -> Result<Vec<DailySummary>, String> {
+    Ok(fetch_daily_records(db)?
+        .into_iter()
+        .map(|d| DailySummary {
+            date: d.date,
+            summary: d.summary.without_trades(),
+        })
+        .collect())
+}
+
+/// 取引日を週・月・四半期・年で区切って集計し、直前の期間からの変化を付ける
+pub fn fetch_period_records(
+    db: &DbState,
+    period: SummaryPeriod,
+    filter: Option<TradeFilter>,
+) -> Result<Vec<PeriodSummary>, String> {
+    let trades = match filter {
+        Some(f) => trades::get_by_filter(db, f)?,
+        None => trades::get_all_trades(db)?,
+    };
+
+    // 期間の初日ごとの trades
+    let mut map: HashMap<chrono::NaiveDate, Vec<Trade>> = HashMap::new();
+    for t in trades {
+        let start = period.start_of(get_business_date_from_unix(t.exit_time));
+        map.entry(start).or_insert_with(Vec::new).push(t);
+    }
+
+    let mut starts: Vec<chrono::NaiveDate> = map.keys().copied().collect();
+    starts.sort();
+
+    let mut result: Vec<PeriodSummary> = Vec::new();
+    for start in starts {
+        let trades = map.remove(&start).unwrap_or_default();
+        // トレードは get_trades_page で取得するので集計だけ返す
+        let summary = TradeSummary::from_trades(trades).without_trades();
+        let change = result.last().map(|prev| change_from(prev, &summary));
+
+        result.push(PeriodSummary {
+            period,
+            label: period.label(start),
+            start,
+            end: period.end_of(start),
+            summary,
+            change,
+        });
+    }
+
+    Ok(result)
+}
+
+fn change_from(prev: &PeriodSummary, current: &TradeSummary) -> PeriodChange {
+    let win_rate = |s: &TradeSummary| {
+        if s.count > 0 {
+            s.wins as f64 / s.count as f64
+        } else {
+            0.0
+        }
+    };
+    let profit = current.profit - prev.summary.profit;
+
+    PeriodChange {
+        previous: prev.label.clone(),
+        profit,
+        profit_pips: current.profit_pips - prev.summary.profit_pips,
+        profit_pct: if prev.summary.profit != 0 {
+            Some(profit as f64 / prev.summary.profit.abs() as f64 * 100.0)
+        } else {
+            None
+        },
+        count: current.count - prev.summary.count,
+        win_rate: win_rate(current) - win_rate(&prev.summary),
+    }
+}
+
 pub fn update_trade_memo_by_id(db: &DbState, trade: Trade) -> Result<(), String> {
     trades::update_trade_memo_by_id(db, trade)
 }
@@ -0,0 +1,182 @@
+use std::sync::{Mutex, RwLock};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::service::scheduler_status::SchedulerStatus;
+use crate::models::settings::sync_scheduler::SyncSchedulerConfig;
+use crate::service::sync::{SyncState, SYNC_PROGRESS_EVENT};
+
+// スケジューラーの状態を通知するイベント名
+pub const SCHEDULER_STATUS_EVENT: &str = "sync-scheduler-status";
+
+// 起動直後はブリッジの立ち上がりを待ってから同期する
+const STARTUP_DELAY_SECS: i64 = 30;
+
+// 実行予定・一時停止を確認する間隔
+const TICK_SECS: u64 = 1;
+
+// 期限を指定しない一時停止は 5 分で自動的に解除する
+const DEFAULT_PAUSE_SECS: i64 = 300;
+
+pub struct SchedulerState {
+    config: RwLock<SyncSchedulerConfig>,
+    status: Mutex<SchedulerStatus>,
+}
+
+impl SchedulerState {
+    pub fn new(config: SyncSchedulerConfig) -> Self {
+        let status = SchedulerStatus {
+            enabled: config.enabled,
+            next_run_at: Some(now() + STARTUP_DELAY_SECS),
+            ..Default::default()
+        };
+
+        SchedulerState {
+            config: RwLock::new(config),
+            status: Mutex::new(status),
+        }
+    }
+
+    pub fn config(&self) -> Result<SyncSchedulerConfig, String> {
+        let config = self.config.read().map_err(|e| e.to_string())?;
+        Ok(config.clone())
+    }
+
+    pub fn update_config(&self, config: SyncSchedulerConfig) -> Result<SchedulerStatus, String> {
+        // 間隔を短くしたときは次回の予定も前倒しする
+        let next = now() + config.interval_secs as i64;
+        let enabled = config.enabled;
+        *self.config.write().map_err(|e| e.to_string())? = config;
+
+        self.update_status(|s| {
+            s.enabled = enabled;
+            s.next_run_at = Some(s.next_run_at.map_or(next, |t| t.min(next)));
+        })
+    }
+
+    pub fn status(&self) -> Result<SchedulerStatus, String> {
+        let status = self.status.lock().map_err(|e| e.to_string())?;
+        Ok(status.clone())
+    }
+
+    /// seconds 秒だけ定期同期を止める（重い画面操作の間など）
+    pub fn pause(&self, seconds: Option<i64>) -> Result<SchedulerStatus, String> {
+        let until = now() + seconds.unwrap_or(DEFAULT_PAUSE_SECS);
+        self.update_status(|s| s.paused_until = Some(until))
+    }
+
+    pub fn resume(&self) -> Result<SchedulerStatus, String> {
+        self.update_status(|s| s.paused_until = None)
+    }
+
+    fn update_status<F>(&self, f: F) -> Result<SchedulerStatus, String>
+    where
+        F: FnOnce(&mut SchedulerStatus),
+    {
+        let mut status = self.status.lock().map_err(|e| e.to_string())?;
+        f(&mut status);
+        Ok(status.clone())
+    }
+}
+
+/// アプリ終了まで定期同期を回し続ける
+pub async fn run_scheduler(app: AppHandle) {
+    loop {
+        tokio::time::sleep(Duration::from_secs(TICK_SECS)).await;
+
+        if let Err(e) = tick(&app).await {
+            eprintln!("❌ Scheduled sync failed: {}", e);
+        }
+    }
+}
+
+async fn tick(app: &AppHandle) -> Result<(), String> {
+    let scheduler = app.state::<SchedulerState>();
+    let config = scheduler.config()?;
+    let status = scheduler.status()?;
+    let started_at = now();
+
+    if !config.enabled
+        || status.paused_until.is_some_and(|t| t > started_at)
+        || status.next_run_at.is_some_and(|t| t > started_at)
+    {
+        return Ok(());
+    }
+
+    let next = started_at + config.interval_secs as i64;
+    let market = app.state::<MarketDataState>();
+
+    // ブリッジに届かないときは次回に回す
+    if market.provider()?.ping().await.is_err() {
+        let status = scheduler.update_status(|s| {
+            s.bridge_reachable = Some(false);
+            s.next_run_at = Some(next);
+        })?;
+        let _ = app.emit(SCHEDULER_STATUS_EVENT, status);
+        return Ok(());
+    }
+
+    // 手動同期が実行中なら今回は見送る
+    let sync = app.state::<SyncState>();
+    let guard = match sync.begin() {
+        Ok(guard) => guard,
+        Err(_) => {
+            scheduler.update_status(|s| {
+                s.bridge_reachable = Some(true);
+                s.next_run_at = Some(next);
+            })?;
+            return Ok(());
+        }
+    };
+
+    let status = scheduler.update_status(|s| {
+        s.bridge_reachable = Some(true);
+        s.syncing = true;
+        s.last_run_at = Some(started_at);
+        s.next_run_at = Some(next);
+    })?;
+    let _ = app.emit(SCHEDULER_STATUS_EVENT, status);
+
+    let db = app.state::<DbState>();
+    let result = crate::service::sync::run_sync(
+        &db,
+        &market,
+        &config.kinds,
+        &guard.cancel,
+        |p| {
+            let _ = app.emit(SYNC_PROGRESS_EVENT, p.clone());
+        },
+    )
+    .await;
+    drop(guard);
+
+    let status = scheduler.update_status(|s| {
+        let finished_at = now();
+        s.syncing = false;
+        s.last_finished_at = Some(finished_at);
+        s.next_run_at = Some(finished_at + config.interval_secs as i64);
+        s.runs += 1;
+
+        match &result {
+            Ok(summary) => {
+                s.last_candles = summary.candles;
+                s.last_ticks = summary.ticks;
+                s.last_errors = summary.errors.clone();
+            }
+            Err(e) => {
+                s.last_candles = 0;
+                s.last_ticks = 0;
+                s.last_errors = vec![e.clone()];
+            }
+        }
+    })?;
+    let _ = app.emit(SCHEDULER_STATUS_EVENT, status);
+
+    Ok(())
+}
+
+fn now() -> i64 {
+    chrono::Utc::now().timestamp()
+}
@@ -0,0 +1,21 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::db::queries::meta;
+use crate::db::DbState;
+
+/// meta に JSON で保存された設定を読み込む。未保存なら既定値を返す
+pub fn load<T: DeserializeOwned + Default>(db: &DbState, key: &str) -> Result<T, String> {
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+
+    match meta::get_meta(&conn, key)? {
+        Some(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+        None => Ok(T::default()),
+    }
+}
+
+pub fn save<T: Serialize>(db: &DbState, key: &str, value: &T) -> Result<(), String> {
+    let json = serde_json::to_string(value).map_err(|e| e.to_string())?;
+    let conn = db.conn.lock().map_err(|e| e.to_string())?;
+    meta::set_meta(&conn, key, &json)
+}
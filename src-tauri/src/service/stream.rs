@@ -0,0 +1,124 @@
+use crate::db::queries::{candles, ticks};
+use crate::db::DbState;
+use crate::models::service::columnar::{CandleColumns, TickColumns};
+use crate::models::settings::market_data::BASE_TIMEFRAME;
+use crate::service::candles::CandleAggregator;
+
+// DB から一度に読む件数（読み込みごとにロックを解放する）
+const CANDLE_PAGE_SIZE: i64 = 20000;
+const TICK_PAGE_SIZE: i64 = 50000;
+
+// フロントエンドへ送る 1 チャンクあたりの件数
+const CANDLE_CHUNK_SIZE: usize = 5000;
+const TICK_CHUNK_SIZE: usize = 20000;
+
+/// pair の [from, to) の M1 ローソク足を interval 秒に集約し、バイナリチャンクとして send に渡す
+/// 最後のチャンクは is_last = 1 で必ず送る。戻り値は送った足の総数
+pub fn stream_candles<F>(
+    db: &DbState,
+    pair: &str,
+    interval: i64,
+    from: Option<i64>,
+    to: Option<i64>,
+    mut send: F,
+) -> Result<usize, String>
+where
+    F: FnMut(Vec<u8>) -> Result<(), String>,
+{
+    if interval <= 0 {
+        return Err("interval は1以上を指定してください".into());
+    }
+
+    let mut cursor = from.unwrap_or(0);
+    let to = to.unwrap_or(i64::MAX);
+
+    let mut aggregator = CandleAggregator::new(interval);
+    let mut columns = CandleColumns::with_capacity(CANDLE_CHUNK_SIZE);
+    let mut total = 0;
+
+    loop {
+        let page =
+            candles::find_candles_page(db, pair, BASE_TIMEFRAME, cursor, to, CANDLE_PAGE_SIZE)?;
+        let done = (page.len() as i64) < CANDLE_PAGE_SIZE;
+        if let Some(last) = page.last() {
+            // pair・timeframe ごとに time は UNIQUE なので次の秒から読めば重複しない
+            cursor = last.time + 1;
+        }
+
+        for candle in &page {
+            if let Some(bar) = aggregator.push(candle) {
+                columns.push(&bar);
+                if columns.len() >= CANDLE_CHUNK_SIZE {
+                    total += columns.len();
+                    send(columns.to_bytes(false))?;
+                    columns.clear();
+                }
+            }
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    if let Some(bar) = aggregator.finish() {
+        columns.push(&bar);
+    }
+    total += columns.len();
+    send(columns.to_bytes(true))?;
+
+    Ok(total)
+}
+
+/// pair の [from, to]（ミリ秒）のティックをバイナリチャンクとして send に渡す
+/// 最後のチャンクは is_last = 1 で必ず送る。戻り値は送ったティックの総数
+pub fn stream_ticks<F>(
+    db: &DbState,
+    pair: &str,
+    from: i64,
+    to: i64,
+    mut send: F,
+) -> Result<usize, String>
+where
+    F: FnMut(Vec<u8>) -> Result<(), String>,
+{
+    let mut cursor = from;
+    let mut columns = TickColumns::with_capacity(TICK_CHUNK_SIZE);
+    let mut total = 0;
+
+    loop {
+        let mut page = ticks::find_ticks_page(db, pair, cursor, to, TICK_PAGE_SIZE)?;
+        let done = (page.len() as i64) < TICK_PAGE_SIZE;
+
+        if !done {
+            // 同じ time_msc のティックがページ境界をまたぐことがあるので、
+            // 末尾と同じ時刻のものは次のページで読み直す
+            let last_msc = page.last().map(|t| t.time_msc).unwrap_or(cursor);
+            let keep = page.iter().take_while(|t| t.time_msc < last_msc).count();
+            if keep > 0 {
+                page.truncate(keep);
+                cursor = last_msc;
+            } else {
+                cursor = last_msc + 1;
+            }
+        }
+
+        for tick in &page {
+            columns.push(tick);
+            if columns.len() >= TICK_CHUNK_SIZE {
+                total += columns.len();
+                send(columns.to_bytes(false))?;
+                columns.clear();
+            }
+        }
+
+        if done {
+            break;
+        }
+    }
+
+    total += columns.len();
+    send(columns.to_bytes(true))?;
+
+    Ok(total)
+}
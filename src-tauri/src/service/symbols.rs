@@ -0,0 +1,90 @@
+use crate::db::queries::symbols;
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::db::symbol::SymbolSpec;
+use crate::models::settings::market_data::{MarketDataConfig, MARKET_DATA_CONFIG_KEY};
+use crate::service::settings;
+
+// カタログにない銘柄の価格単位（ドル円: 0.001）
+pub const DEFAULT_POINT: f64 = 0.001;
+
+/// 取引の通貨ペアを相場データの銘柄名にする（"USD/JPY" → "USDJPY"）
+pub fn market_symbol(pair: &str) -> String {
+    pair.replace('/', "")
+}
+
+/// 銘柄の価格単位。"USD/JPY" のような表記でもカタログを引く
+pub fn point_of(db: &DbState, pair: &str) -> Result<f64, String> {
+    let spec = match symbols::find_by_name(db, pair)? {
+        Some(s) => Some(s),
+        None => symbols::find_by_name(db, &market_symbol(pair))?,
+    };
+    Ok(spec
+        .map(|s| s.point)
+        .filter(|p| *p > 0.0)
+        .unwrap_or(DEFAULT_POINT))
+}
+
+/// ブリッジから銘柄の仕様を取得して保存し、カタログを返す
+pub async fn sync_catalog(
+    db: &DbState,
+    market: &MarketDataState,
+) -> Result<Vec<SymbolSpec>, String> {
+    let provider = market.provider()?;
+    let mut fetched = provider.fetch_symbols().await?;
+
+    let now = chrono::Utc::now().timestamp();
+    for s in &mut fetched {
+        s.updated_at = now;
+    }
+    symbols::upsert_symbols_bulk(db, &fetched)?;
+
+    get_catalog(db, market)
+}
+
+/// 保存済みのカタログ。同期対象の銘柄には selected を付ける
+pub fn get_catalog(db: &DbState, market: &MarketDataState) -> Result<Vec<SymbolSpec>, String> {
+    let config = market.config()?;
+    let mut catalog = symbols::get_all_symbols(db)?;
+    for s in &mut catalog {
+        s.selected = config.symbols.contains(&s.name);
+    }
+    Ok(catalog)
+}
+
+/// ローソク足・ティックの同期対象にする銘柄を選ぶ
+/// カタログを取得済みなら、カタログにない銘柄は選べない
+pub fn select_symbols(
+    db: &DbState,
+    market: &MarketDataState,
+    selected: Vec<String>,
+) -> Result<MarketDataConfig, String> {
+    let mut names: Vec<String> = Vec::new();
+    for name in selected {
+        let name = name.trim().to_string();
+        if !name.is_empty() && !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    if names.is_empty() {
+        return Err("同期する銘柄を 1 つ以上選んでください".into());
+    }
+
+    let catalog = symbols::get_all_symbols(db)?;
+    if !catalog.is_empty() {
+        let unknown: Vec<&str> = names
+            .iter()
+            .filter(|n| !catalog.iter().any(|s| &s.name == *n))
+            .map(|n| n.as_str())
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!("カタログにない銘柄です: {}", unknown.join(", ")));
+        }
+    }
+
+    let mut config = market.config()?;
+    config.symbols = names;
+    settings::save(db, MARKET_DATA_CONFIG_KEY, &config)?;
+    market.reload(config.clone())?;
+    Ok(config)
+}
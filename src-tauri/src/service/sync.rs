@@ -0,0 +1,322 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::db::queries::{candles, ticks};
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::service::sync::{LastSync, SyncKind, SyncProgress, SyncStatus, SyncSummary};
+use crate::service::settings;
+
+// 同期の進捗を通知するイベント名
+pub const SYNC_PROGRESS_EVENT: &str = "market-sync-progress";
+
+// ブリッジのエラー時は 1, 2, 4, 8, 16 秒待って再試行する
+const MAX_RETRIES: u32 = 5;
+const BASE_BACKOFF_MS: u64 = 1000;
+
+// キャンセル確認の間隔
+const CANCEL_POLL_MS: u64 = 200;
+
+/// 実行中の同期ジョブ（同時に 1 つだけ）
+#[derive(Default)]
+pub struct SyncState {
+    running: Mutex<Option<Arc<AtomicBool>>>,
+}
+
+/// 同期の実行権。drop で実行中フラグを下ろす
+pub struct SyncGuard<'a> {
+    state: &'a SyncState,
+    pub cancel: Arc<AtomicBool>,
+}
+
+impl SyncState {
+    pub fn begin(&self) -> Result<SyncGuard<'_>, String> {
+        let mut running = self.running.lock().map_err(|e| e.to_string())?;
+        if running.is_some() {
+            return Err("同期処理が既に実行中です".into());
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        *running = Some(cancel.clone());
+        Ok(SyncGuard {
+            state: self,
+            cancel,
+        })
+    }
+
+    /// 実行中のジョブにキャンセルを要求する。実行中でなければ false
+    pub fn cancel(&self) -> Result<bool, String> {
+        let running = self.running.lock().map_err(|e| e.to_string())?;
+        match running.as_ref() {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.lock().map(|r| r.is_some()).unwrap_or(false)
+    }
+}
+
+impl Drop for SyncGuard<'_> {
+    fn drop(&mut self) {
+        if let Ok(mut running) = self.state.running.lock() {
+            *running = None;
+        }
+    }
+}
+
+/// 設定された全銘柄について、最新データに追いつくまでページングして取得する
+/// 銘柄ごとのエラーは errors に積んで次の銘柄へ進む
+pub async fn run_sync<P>(
+    db: &DbState,
+    market: &MarketDataState,
+    kinds: &[SyncKind],
+    cancel: &AtomicBool,
+    on_progress: P,
+) -> Result<SyncSummary, String>
+where
+    P: Fn(&SyncProgress) + Sync,
+{
+    let config = market.config()?;
+    let mut summary = SyncSummary::default();
+
+    for kind in kinds {
+        // ティックは足種を持たない
+        let timeframes: Vec<Option<String>> = match kind {
+            SyncKind::Ohlc => config.timeframes.iter().cloned().map(Some).collect(),
+            SyncKind::Tick => vec![None],
+        };
+
+        for symbol in &config.symbols {
+            for timeframe in &timeframes {
+                if cancel.load(Ordering::SeqCst) {
+                    summary.cancelled = true;
+                    return Ok(summary);
+                }
+
+                let progress =
+                    sync_one(db, market, *kind, symbol, timeframe.clone(), cancel, &on_progress)
+                        .await;
+
+                match progress.status {
+                    SyncStatus::Cancelled => summary.cancelled = true,
+                    SyncStatus::Failed => summary.errors.push(format!(
+                        "{} {}: {}",
+                        symbol,
+                        timeframe.as_deref().unwrap_or("tick"),
+                        progress.message.clone().unwrap_or_default()
+                    )),
+                    _ => save_last_sync(db, &progress)?,
+                }
+
+                match kind {
+                    SyncKind::Ohlc => summary.candles += progress.fetched,
+                    SyncKind::Tick => summary.ticks += progress.fetched,
+                }
+
+                if summary.cancelled {
+                    return Ok(summary);
+                }
+            }
+        }
+    }
+
+    Ok(summary)
+}
+
+/// 1 銘柄・1 足種を最新まで取得する。戻り値は最終的な進捗
+async fn sync_one<P>(
+    db: &DbState,
+    market: &MarketDataState,
+    kind: SyncKind,
+    symbol: &str,
+    timeframe: Option<String>,
+    cancel: &AtomicBool,
+    on_progress: &P,
+) -> SyncProgress
+where
+    P: Fn(&SyncProgress) + Sync,
+{
+    let mut progress = SyncProgress {
+        kind,
+        symbol: symbol.to_string(),
+        timeframe: timeframe.clone(),
+        status: SyncStatus::Running,
+        fetched: 0,
+        latest_time: 0,
+        attempt: 0,
+        message: None,
+    };
+
+    let result = async {
+        let provider = market.provider()?;
+        let timeframe = timeframe.as_deref().unwrap_or_default();
+        progress.latest_time = latest_time(db, kind, symbol, timeframe)?;
+        on_progress(&progress);
+
+        loop {
+            if cancel.load(Ordering::SeqCst) {
+                progress.status = SyncStatus::Cancelled;
+                return Ok(());
+            }
+
+            let since = progress.latest_time;
+            let inserted = match kind {
+                SyncKind::Ohlc => {
+                    let fetched = with_retry(cancel, &mut progress, on_progress, || {
+                        provider.fetch_ohlc(symbol, timeframe, since)
+                    })
+                    .await?;
+                    match fetched {
+                        Some(batch) if batch.is_empty() => break,
+                        Some(batch) => candles::insert_candles_bulk(db, timeframe, &batch)?,
+                        None => break,
+                    }
+                }
+                SyncKind::Tick => {
+                    let fetched = with_retry(cancel, &mut progress, on_progress, || {
+                        provider.fetch_ticks(symbol, since)
+                    })
+                    .await?;
+                    match fetched {
+                        Some(batch) if batch.is_empty() => break,
+                        Some(batch) => ticks::insert_ticks_bulk(db, &batch)?,
+                        None => break,
+                    }
+                }
+            };
+
+            progress.fetched += inserted;
+            progress.latest_time = latest_time(db, kind, symbol, timeframe)?;
+            progress.status = SyncStatus::Running;
+            progress.attempt = 0;
+            progress.message = None;
+            on_progress(&progress);
+
+            // 最新時刻が進まなければ追いついた
+            if progress.latest_time <= since {
+                break;
+            }
+        }
+
+        Ok::<(), String>(())
+    }
+    .await;
+
+    if let Err(e) = result {
+        progress.status = SyncStatus::Failed;
+        progress.message = Some(e);
+    } else if cancel.load(Ordering::SeqCst) {
+        progress.status = SyncStatus::Cancelled;
+    } else {
+        progress.status = SyncStatus::Done;
+    }
+    on_progress(&progress);
+
+    progress
+}
+
+/// fetch をバックオフ付きで再試行する。待機中にキャンセルされたら None
+async fn with_retry<T, F, Fut, P>(
+    cancel: &AtomicBool,
+    progress: &mut SyncProgress,
+    on_progress: &P,
+    fetch: F,
+) -> Result<Option<T>, String>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, String>>,
+    P: Fn(&SyncProgress) + Sync,
+{
+    let mut attempt = 0;
+    loop {
+        match fetch().await {
+            Ok(value) => return Ok(Some(value)),
+            Err(e) if attempt < MAX_RETRIES => {
+                attempt += 1;
+                progress.status = SyncStatus::Retrying;
+                progress.attempt = attempt;
+                progress.message = Some(e);
+                on_progress(progress);
+
+                let delay = BASE_BACKOFF_MS * 2u64.pow(attempt - 1);
+                if !sleep_unless_cancelled(cancel, delay).await {
+                    return Ok(None);
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// ms だけ待つ。途中でキャンセルされたら false
+pub async fn sleep_unless_cancelled(cancel: &AtomicBool, ms: u64) -> bool {
+    let mut waited = 0;
+    while waited < ms {
+        if cancel.load(Ordering::SeqCst) {
+            return false;
+        }
+        let step = CANCEL_POLL_MS.min(ms - waited);
+        tokio::time::sleep(Duration::from_millis(step)).await;
+        waited += step;
+    }
+    !cancel.load(Ordering::SeqCst)
+}
+
+fn latest_time(db: &DbState, kind: SyncKind, symbol: &str, timeframe: &str) -> Result<i64, String> {
+    match kind {
+        SyncKind::Ohlc => candles::get_latest_time(db, symbol, timeframe),
+        SyncKind::Tick => ticks::get_latest_time(db, symbol),
+    }
+}
+
+fn last_sync_key(kind: SyncKind, symbol: &str, timeframe: Option<&str>) -> String {
+    match timeframe {
+        Some(tf) => format!("last_sync:{}:{}:{}", kind.as_str(), symbol, tf),
+        None => format!("last_sync:{}:{}", kind.as_str(), symbol),
+    }
+}
+
+fn save_last_sync(db: &DbState, progress: &SyncProgress) -> Result<(), String> {
+    let record = LastSync {
+        kind: Some(progress.kind),
+        symbol: progress.symbol.clone(),
+        timeframe: progress.timeframe.clone(),
+        synced_at: chrono::Utc::now().timestamp(),
+        latest_time: progress.latest_time,
+        fetched: progress.fetched,
+    };
+
+    let key = last_sync_key(progress.kind, &progress.symbol, progress.timeframe.as_deref());
+    settings::save(db, &key, &record)
+}
+
+/// 設定中の銘柄について最後に成功した同期を返す（未同期のものは含めない）
+pub fn get_last_syncs(db: &DbState, market: &MarketDataState) -> Result<Vec<LastSync>, String> {
+    let config = market.config()?;
+    let mut result = Vec::new();
+
+    for symbol in &config.symbols {
+        let mut keys: Vec<String> = config
+            .timeframes
+            .iter()
+            .map(|tf| last_sync_key(SyncKind::Ohlc, symbol, Some(tf)))
+            .collect();
+        keys.push(last_sync_key(SyncKind::Tick, symbol, None));
+
+        for key in keys {
+            let record: LastSync = settings::load(db, &key)?;
+            if record.kind.is_some() {
+                result.push(record);
+            }
+        }
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,337 @@
+use reqwest::{Client, Response};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::db::queries::ticks;
+use crate::db::DbState;
+use crate::market_data::MarketDataState;
+use crate::models::db::tick::Tick;
+use crate::models::service::tick_stream::TickStreamStatus;
+use crate::service::sync::sleep_unless_cancelled;
+
+// 受信したティックを通知するイベント名
+pub const TICK_STREAM_EVENT: &str = "tick-stream";
+
+// 受信状態を通知するイベント名
+pub const TICK_STREAM_STATUS_EVENT: &str = "tick-stream-status";
+
+// 溜まったティックはこの間隔か件数で書き込む
+const FLUSH_MS: u64 = 500;
+const FLUSH_SIZE: usize = 200;
+
+// ブリッジは 15 秒ごとにハートビートを送るので、これだけ無音なら切断とみなす
+const IDLE_TIMEOUT_SECS: u64 = 45;
+
+// 再接続は 1, 2, 4 ... 最大 30 秒待つ
+const MAX_BACKOFF_MS: u64 = 30_000;
+
+/// 実行中のティック受信（同時に 1 つだけ）
+#[derive(Default)]
+pub struct TickStreamState {
+    running: Mutex<Option<Arc<AtomicBool>>>,
+    status: Mutex<TickStreamStatus>,
+}
+
+impl TickStreamState {
+    fn begin(&self, symbols: &[String]) -> Result<Arc<AtomicBool>, String> {
+        let mut running = self.running.lock().map_err(|e| e.to_string())?;
+        if running.is_some() {
+            return Err("ティック受信は既に実行中です".into());
+        }
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        *running = Some(cancel.clone());
+
+        self.update_status(|s| {
+            *s = TickStreamStatus {
+                running: true,
+                symbols: symbols.to_vec(),
+                ..Default::default()
+            }
+        })?;
+        Ok(cancel)
+    }
+
+    fn finish(&self, cancel: &Arc<AtomicBool>) {
+        if let Ok(mut running) = self.running.lock() {
+            if running.as_ref().is_some_and(|c| Arc::ptr_eq(c, cancel)) {
+                *running = None;
+            }
+        }
+        let _ = self.update_status(|s| {
+            s.running = false;
+            s.connected = false;
+            s.next_retry_at = None;
+        });
+    }
+
+    /// 受信を止める。実行中でなければ false
+    pub fn stop(&self) -> Result<bool, String> {
+        let running = self.running.lock().map_err(|e| e.to_string())?;
+        match running.as_ref() {
+            Some(flag) => {
+                flag.store(true, Ordering::SeqCst);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    pub fn status(&self) -> Result<TickStreamStatus, String> {
+        let status = self.status.lock().map_err(|e| e.to_string())?;
+        Ok(status.clone())
+    }
+
+    fn update_status<F>(&self, f: F) -> Result<TickStreamStatus, String>
+    where
+        F: FnOnce(&mut TickStreamStatus),
+    {
+        let mut status = self.status.lock().map_err(|e| e.to_string())?;
+        f(&mut status);
+        Ok(status.clone())
+    }
+}
+
+/// ブリッジの /stream_ticks (Server-Sent Events) を受信するタスクを起動する
+pub fn start(app: &AppHandle, symbols: Vec<String>) -> Result<TickStreamStatus, String> {
+    let state = app.state::<TickStreamState>();
+    let cancel = state.begin(&symbols)?;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run(&app, &symbols, &cancel).await;
+
+        let state = app.state::<TickStreamState>();
+        state.finish(&cancel);
+        if let Ok(status) = state.status() {
+            let _ = app.emit(TICK_STREAM_STATUS_EVENT, status);
+        }
+    });
+
+    state.status()
+}
+
+/// 停止されるまで接続と再接続を繰り返す
+async fn run(app: &AppHandle, symbols: &[String], cancel: &AtomicBool) {
+    // 接続を保ち続けるので全体のタイムアウトは付けない
+    let client = Client::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_else(|_| Client::new());
+
+    let state = app.state::<TickStreamState>();
+    let mut failures = 0;
+
+    while !cancel.load(Ordering::SeqCst) {
+        let (received, error) = stream_once(app, &client, symbols, cancel).await;
+        if cancel.load(Ordering::SeqCst) {
+            break;
+        }
+
+        // 一度でも受信できていればバックオフをやり直す
+        failures = if received { 0 } else { failures + 1 };
+        let delay = match failures {
+            0 => 1000,
+            n => (1000 * 2u64.pow((n - 1).min(5))).min(MAX_BACKOFF_MS),
+        };
+        let reason = error.unwrap_or_else(|| "ブリッジが接続を閉じました".to_string());
+        eprintln!("Tick stream disconnected: {}", reason);
+
+        if let Ok(status) = state.update_status(|s| {
+            s.connected = false;
+            s.reconnects += 1;
+            s.last_error = Some(reason);
+            s.next_retry_at = Some(chrono::Utc::now().timestamp() + (delay / 1000) as i64);
+        }) {
+            let _ = app.emit(TICK_STREAM_STATUS_EVENT, status);
+        }
+
+        if !sleep_unless_cancelled(cancel, delay).await {
+            break;
+        }
+    }
+}
+
+/// 1 回分の接続。(ティックを受け取れたか, 切断の理由) を返す
+/// 停止されたときは理由なし。切断するときも溜まったティックは書き込む
+async fn stream_once(
+    app: &AppHandle,
+    client: &Client,
+    symbols: &[String],
+    cancel: &AtomicBool,
+) -> (bool, Option<String>) {
+    let db = app.state::<DbState>();
+    let market = app.state::<MarketDataState>();
+    let state = app.state::<TickStreamState>();
+
+    // 切断していた間のティックを先に埋める
+    backfill(&db, &market, symbols).await;
+
+    let mut resp = match connect(client, &market, symbols).await {
+        Ok(r) => r,
+        Err(e) => return (false, Some(e)),
+    };
+
+    if let Ok(status) = state.update_status(|s| {
+        s.connected = true;
+        s.next_retry_at = None;
+    }) {
+        let _ = app.emit(TICK_STREAM_STATUS_EVENT, status);
+    }
+
+    let mut parser = SseParser::default();
+    let mut pending: Vec<Tick> = Vec::new();
+    let mut received_any = false;
+    let mut last_flush = Instant::now();
+    let mut last_data = Instant::now();
+
+    let error = loop {
+        if cancel.load(Ordering::SeqCst) {
+            break None;
+        }
+
+        match next_chunk(&mut resp).await {
+            Ok(Some(chunk)) => {
+                last_data = Instant::now();
+                for data in parser.push(&chunk) {
+                    match serde_json::from_str::<Tick>(&data) {
+                        Ok(tick) => pending.push(tick),
+                        Err(e) => eprintln!("Invalid tick event: {}", e),
+                    }
+                }
+            }
+            Ok(None) if last_data.elapsed() > Duration::from_secs(IDLE_TIMEOUT_SECS) => {
+                break Some("ブリッジからの応答が途絶えました".to_string());
+            }
+            Ok(None) => {}
+            Err(e) => break Some(e),
+        }
+
+        if pending.len() >= FLUSH_SIZE
+            || (!pending.is_empty() && last_flush.elapsed() >= Duration::from_millis(FLUSH_MS))
+        {
+            received_any = true;
+            if let Err(e) = flush(app, &db, &state, &mut pending) {
+                break Some(e);
+            }
+            last_flush = Instant::now();
+        }
+    };
+
+    received_any |= !pending.is_empty();
+    match flush(app, &db, &state, &mut pending) {
+        Ok(()) => (received_any, error),
+        Err(e) => (received_any, error.or(Some(e))),
+    }
+}
+
+/// /stream_ticks に接続する
+async fn connect(
+    client: &Client,
+    market: &MarketDataState,
+    symbols: &[String],
+) -> Result<Response, String> {
+    let url = format!("{}/stream_ticks", market.config()?.base_url());
+    client
+        .get(url)
+        .query(&[("symbols", symbols.join(","))])
+        .header("Accept", "text/event-stream")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?
+        .error_for_status()
+        .map_err(|e| e.to_string())
+}
+
+/// 次のチャンクを待つ。FLUSH_MS 以内に届かなければ None
+/// 接続が閉じられたら Err
+async fn next_chunk(resp: &mut Response) -> Result<Option<Vec<u8>>, String> {
+    match tokio::time::timeout(Duration::from_millis(FLUSH_MS), resp.chunk()).await {
+        Ok(Ok(Some(chunk))) => Ok(Some(chunk.to_vec())),
+        Ok(Ok(None)) => Err("ブリッジが接続を閉じました".into()),
+        Ok(Err(e)) => Err(e.to_string()),
+        Err(_) => Ok(None),
+    }
+}
+
+/// ticks に書き込み、フロントへ転送する
+fn flush(
+    app: &AppHandle,
+    db: &DbState,
+    state: &TickStreamState,
+    pending: &mut Vec<Tick>,
+) -> Result<(), String> {
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    let written = ticks::insert_ticks_bulk(db, pending)?;
+    let _ = app.emit(TICK_STREAM_EVENT, pending.clone());
+
+    let received = pending.len();
+    let last_tick_time = pending.iter().map(|t| t.time).max();
+    state.update_status(|s| {
+        s.received += received;
+        s.written += written;
+        s.last_tick_time = last_tick_time.or(s.last_tick_time);
+        s.last_error = None;
+    })?;
+
+    pending.clear();
+    Ok(())
+}
+
+/// 保存済みの最新ティック以降を 1 ページだけ取得する
+/// まだティックがない通貨ペアは定期同期に任せる
+async fn backfill(db: &DbState, market: &MarketDataState, symbols: &[String]) {
+    let provider = match market.provider() {
+        Ok(p) => p,
+        Err(_) => return,
+    };
+
+    for symbol in symbols {
+        let since = match ticks::find_latest(db, symbol) {
+            Ok(Some(tick)) => tick.time,
+            _ => continue,
+        };
+
+        match provider.fetch_ticks(symbol, since).await {
+            Ok(batch) => {
+                let _ = ticks::insert_ticks_bulk(db, &batch);
+            }
+            Err(e) => eprintln!("Tick backfill failed ({}): {}", symbol, e),
+        }
+    }
+}
+
+/// Server-Sent Events の data 行を取り出す
+/// イベントはチャンクの途中で分かれて届くことがある
+#[derive(Default)]
+struct SseParser {
+    buf: Vec<u8>,
+}
+
+impl SseParser {
+    fn push(&mut self, chunk: &[u8]) -> Vec<String> {
+        self.buf.extend(chunk.iter().filter(|&&b| b != b'\r'));
+
+        let mut events = Vec::new();
+        while let Some(pos) = self.buf.windows(2).position(|w| w == b"\n\n") {
+            let event: Vec<u8> = self.buf.drain(..pos + 2).collect();
+            let text = String::from_utf8_lossy(&event);
+
+            // ":" で始まる行はハートビートなどのコメント
+            let data: Vec<&str> = text
+                .lines()
+                .filter_map(|line| line.strip_prefix("data:"))
+                .map(|d| d.trim_start())
+                .collect();
+            if !data.is_empty() {
+                events.push(data.join("\n"));
+            }
+        }
+        events
+    }
+}
@@ -0,0 +1,119 @@
+use crate::db::queries::{candles, daily_memo, trades};
+use crate::db::DbState;
+use crate::models::db::trade::Trade;
+use crate::models::service::execution::FillKind;
+use crate::models::service::trade_context::{TradeContext, TradeMarker};
+use crate::models::settings::market_data::{BASE_TIMEFRAME, MAX_BASE_CANDLES};
+use crate::service::candles::CandleAggregator;
+use crate::service::symbols::market_symbol;
+use crate::utils::time_utils;
+
+// 前後に付ける足の本数の既定値と上限
+pub const DEFAULT_PADDING_BARS: i64 = 50;
+const MAX_PADDING_BARS: i64 = 1_000;
+
+// 1 回に読み込む M1 の本数は MAX_BASE_CANDLES まで、合計はこのページ数まで（約 1 年半）
+const MAX_PAGES: usize = 8;
+
+/// トレードの前後 padding 本ずつを含むローソク足と、エントリー・決済の印、
+/// 重なるトレード、決済日の日次メモをまとめて返す
+pub fn get_context(
+    db: &DbState,
+    trade_id: i64,
+    interval: i64,
+    padding: Option<i64>,
+) -> Result<TradeContext, String> {
+    if interval <= 0 {
+        return Err("足の間隔を指定してください".into());
+    }
+    let padding = padding.unwrap_or(DEFAULT_PADDING_BARS).max(0);
+    if padding > MAX_PADDING_BARS {
+        return Err(format!("前後の足は {} 本までにしてください", MAX_PADDING_BARS));
+    }
+
+    let trade = trades::find_by_id(db, trade_id)?
+        .ok_or_else(|| format!("トレードが見つかりません: {}", trade_id))?;
+
+    let bar_of = |t: i64| t - t.rem_euclid(interval);
+    let from = bar_of(trade.entry_time) - interval * padding;
+    let to = bar_of(trade.exit_time) + interval * (padding + 1);
+
+    let pair = market_symbol(&trade.pair);
+    let mut aggregator = CandleAggregator::new(interval);
+    let mut bars = Vec::new();
+    let mut cursor = from;
+    let mut truncated = false;
+    for page_no in 1.. {
+        let page =
+            candles::find_candles_page(db, &pair, BASE_TIMEFRAME, cursor, to, MAX_BASE_CANDLES)?;
+        for candle in &page {
+            if let Some(bar) = aggregator.push(candle) {
+                bars.push(bar);
+            }
+        }
+
+        match page.last() {
+            Some(last) if page.len() as i64 == MAX_BASE_CANDLES => {
+                if page_no >= MAX_PAGES {
+                    truncated = true;
+                    break;
+                }
+                cursor = last.time + 1;
+            }
+            _ => break,
+        }
+    }
+    if let Some(bar) = aggregator.finish() {
+        bars.push(bar);
+    }
+
+    let overlapping = trades::find_overlapping(db, &pair, from, to, trade_id)?;
+
+    let mut markers = Vec::new();
+    for t in std::iter::once(&trade).chain(overlapping.iter()) {
+        markers.extend(markers_of(t, from, to, &bar_of));
+    }
+    markers.sort_by_key(|m| (m.time, m.trade_id));
+
+    // 日次集計と同じく決済時刻の取引日
+    let date = time_utils::get_business_date_from_unix(trade.exit_time);
+    let (business_day_start, business_day_end) =
+        time_utils::get_unix_range_from_business_date(&date.format("%Y%m%d").to_string())
+            .ok_or_else(|| format!("取引日の範囲を求められません: {}", date))?;
+    let business_date = date.format("%Y-%m-%d").to_string();
+    let memo = daily_memo::get_daily_memo(db, &business_date)?;
+
+    Ok(TradeContext {
+        trade,
+        interval,
+        from,
+        to,
+        candles: bars,
+        truncated,
+        markers,
+        overlapping,
+        business_date,
+        business_day_start,
+        business_day_end,
+        daily_memo: memo,
+    })
+}
+
+/// 表示範囲に入るエントリー・決済の印
+fn markers_of(trade: &Trade, from: i64, to: i64, bar_of: &dyn Fn(i64) -> i64) -> Vec<TradeMarker> {
+    [
+        (FillKind::Entry, trade.entry_time, trade.entry_rate),
+        (FillKind::Exit, trade.exit_time, trade.exit_rate),
+    ]
+    .into_iter()
+    .filter(|(_, time, _)| *time >= from && *time < to)
+    .map(|(kind, time, rate)| TradeMarker {
+        trade_id: trade.id.unwrap_or(0) as i64,
+        kind,
+        side: trade.side.clone(),
+        time,
+        bar_time: bar_of(time),
+        rate,
+    })
+    .collect()
+}
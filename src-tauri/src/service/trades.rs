@@ -1,9 +1,18 @@
-use crate::db::queries::trades;
+use crate::db::queries::{excursions, trade_label, trades};
+use rusqlite::Transaction;
 use crate::models::db::trade::Trade;
 use crate::models::filter::trade_filter::TradeFilter;
+use crate::models::settings::merge::{MergeConfig, MergeTimePolicy, MERGE_CONFIG_KEY};
+use crate::models::service::trade_page::TradePage;
 use crate::models::service::trade_summary::TradeSummary;
+use crate::models::service::trash::{TrashReason, TrashedTrade};
+use crate::service::settings;
 use crate::DbState;
 
+// get_trades_page の 1 ページの件数
+const DEFAULT_PAGE_SIZE: i64 = 100;
+const MAX_PAGE_SIZE: i64 = 1000;
+
 pub fn get_filtered_trades_summary(
     db: &DbState,
     filter: TradeFilter,
@@ -18,12 +27,191 @@ pub fn get_filtered_trades_summary(
         }
     };
 
-    let result = TradeSummary::from_trades(trades);
+    let mut result = TradeSummary::from_trades(trades);
+    result.apply_excursions(&excursions::get_all_by_trade_id(db)?);
 
     Ok(result)
 }
 
-pub fn merge_trades(db: &DbState, ids: Vec<i64>) -> Result<(), String> {
+/// トレード一覧を含まない集計。トレードは get_trades_page で取得する
+pub fn get_filtered_summary(db: &DbState, filter: TradeFilter) -> Result<TradeSummary, String> {
+    Ok(get_filtered_trades_summary(db, filter)?.without_trades())
+}
+
+/// フィルターに合うトレードを新しい順にページ単位で返す
+pub fn get_trades_page(
+    db: &DbState,
+    filter: Option<TradeFilter>,
+    offset: Option<i64>,
+    limit: Option<i64>,
+) -> Result<TradePage, String> {
+    let filter = filter.unwrap_or_default();
+    let offset = offset.unwrap_or(0).max(0);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+
+    Ok(TradePage {
+        trades: trades::get_page_by_filter(db, &filter, offset, limit)?,
+        total: trades::count_by_filter(db, &filter)?,
+        offset,
+        limit,
+    })
+}
+
+/// 手入力のトレードを追加する。pips はレートから計算し直す
+pub fn create_trade(db: &DbState, trade: Trade) -> Result<Trade, String> {
+    let trade = normalize(db, trade)?;
+    let id = trades::insert_manual_trade(db, &trade)?;
+    trades::find_by_id(db, id)?.ok_or("トレードの追加に失敗しました".into())
+}
+
+/// トレードの内容を書き換える。削除済みのトレードは編集できない
+/// マージ・分割したトレードは元のトレードと食い違うので編集できない
+pub fn update_trade(db: &DbState, trade: Trade) -> Result<Trade, String> {
+    let id = trade.id.ok_or("トレードが見つかりません")? as i64;
+    trades::find_by_id(db, id)?.ok_or("トレードが見つかりません")?;
+    if !trades::find_by_merged_to(db, id)?.is_empty() {
+        return Err("マージしたトレードは編集できません。マージを取り消してから編集してください".into());
+    }
+    if trades::get_split_from(db, id)? != 0 {
+        return Err("分割したトレードは編集できません。分割前のトレードをゴミ箱から戻してください".into());
+    }
+
+    let trade = normalize(db, trade)?;
+    trades::update_trade(db, &trade)?;
+    // レートや時刻が変わると計算し直しになる
+    excursions::delete_by_trade_id(db, id)?;
+    trades::find_by_id(db, id)?.ok_or("トレードが見つかりません".into())
+}
+
+/// トレードを削除済みにする。ラベルなどの紐付けは残す
+pub fn delete_trade(db: &DbState, id: i64) -> Result<(), String> {
+    trades::find_by_id(db, id)?.ok_or("トレードが見つかりません")?;
+    trades::delete_by_ids(db, vec![id])
+}
+
+/// ゴミ箱（削除済みのトレード）の一覧
+pub fn get_trash(db: &DbState) -> Result<Vec<TrashedTrade>, String> {
+    trades::get_trashed_trades(db)
+}
+
+/// ゴミ箱から戻す
+/// マージ後のトレードが残っていれば（ゴミ箱にあっても）戻さない（マージの取り消しを使う）
+/// 分割した元のトレードを戻すときは、分割後のトレードを完全に削除する
+pub fn restore_trade(db: &DbState, id: i64) -> Result<Trade, String> {
+    let trashed = trades::find_trashed_by_id(db, id)?.ok_or("ゴミ箱にトレードが見つかりません")?;
+
+    let mut piece_ids = Vec::new();
+    match trashed.reason {
+        TrashReason::Merged => {
+            let merged_to = trashed.merged_to.unwrap_or(0);
+            if trades::find_by_id(db, merged_to)?.is_some() {
+                return Err("マージ後のトレードが残っています。マージを取り消してください".into());
+            }
+            if trades::exists(db, merged_to)? {
+                return Err(
+                    "マージ後のトレードがゴミ箱にあります。マージ後のトレードを戻してマージを取り消すか、完全に削除してください"
+                        .into(),
+                );
+            }
+        }
+        TrashReason::Split => {
+            // 分割後のトレードをさらにマージ・分割していたら、消すと損益が合わなくなる
+            for (piece_id, merged_to, split) in trades::find_split_piece_lineage(db, id)? {
+                if merged_to.is_some() || split {
+                    return Err(format!(
+                        "分割後のトレード {} をマージまたは分割しているため戻せません",
+                        piece_id
+                    ));
+                }
+                piece_ids.push(piece_id);
+            }
+        }
+        TrashReason::Deleted => {}
+    }
+
+    {
+        let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        for piece_id in &piece_ids {
+            trades::purge_by_id_tx(&tx, *piece_id)?;
+        }
+        trades::restore_by_ids_tx(&tx, &[id])?;
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+
+    trades::find_by_id(db, id)?.ok_or("トレードが見つかりません".into())
+}
+
+/// ゴミ箱のトレードを完全に削除する。ラベル・キャンペーンの紐付けも消す
+/// マージ後のトレードが残っている（ゴミ箱にある）元のトレードは、マージの取り消しに使うので消さない
+/// マージ後のトレードも一緒に消すときは消せる。1 件でも消せなければ何も消さない
+pub fn purge_trades(db: &DbState, mut ids: Vec<i64>) -> Result<usize, String> {
+    ids.sort();
+    ids.dedup();
+    for id in &ids {
+        let trashed = trades::find_trashed_by_id(db, *id)?.ok_or("ゴミ箱にトレードが見つかりません")?;
+        if let Some(merged_to) = trashed.merged_to {
+            if !ids.contains(&merged_to) && trades::exists(db, merged_to)? {
+                return Err(format!(
+                    "トレード {} はマージ後のトレードが残っているため削除できません",
+                    id
+                ));
+            }
+        }
+    }
+
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for id in &ids {
+        trades::purge_by_id_tx(&tx, *id)?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+
+    Ok(ids.len())
+}
+
+/// 入力を検証し、レートから pips を計算し直す
+fn normalize(db: &DbState, trade: Trade) -> Result<Trade, String> {
+    let pair = trade.pair.trim().to_string();
+    if pair.is_empty() {
+        return Err("通貨ペアを入力してください".into());
+    }
+    if trade.side != "買" && trade.side != "売" {
+        return Err("売買は「買」か「売」を指定してください".into());
+    }
+    if trade.lot <= 0.0 {
+        return Err("ロットは0より大きい値を入力してください".into());
+    }
+    if trade.entry_rate <= 0.0 || trade.exit_rate <= 0.0 {
+        return Err("レートは0より大きい値を入力してください".into());
+    }
+    if trade.entry_time <= 0 || trade.exit_time < trade.entry_time {
+        return Err("決済時刻はエントリー時刻以降にしてください".into());
+    }
+
+    let point = crate::service::symbols::point_of(db, &pair)?;
+    let direction = if trade.side == "買" { 1.0 } else { -1.0 };
+    let profit_pips = ((trade.exit_rate - trade.entry_rate) / point * direction).round() as i32;
+
+    Ok(Trade {
+        pair,
+        lot: (trade.lot * 1e8).round() / 1e8,
+        profit_pips,
+        memo: trade.memo.trim().to_string(),
+        account: trade.account.trim().to_string(),
+        ..trade
+    })
+}
+
+/// 複数のトレードを 1 つにまとめ、まとめた後の ID を返す
+/// time_policy を省略したら設定に従う
+pub fn merge_trades(
+    db: &DbState,
+    mut ids: Vec<i64>,
+    time_policy: Option<MergeTimePolicy>,
+) -> Result<i64, String> {
+    ids.sort();
+    ids.dedup();
     let trades_result = trades::get_by_ids(db, ids.clone());
     let trades = match trades_result {
         Ok(r) => r,
@@ -32,30 +220,179 @@ pub fn merge_trades(db: &DbState, ids: Vec<i64>) -> Result<(), String> {
             return Err(e);
         }
     };
-    validate(&trades)?;
+    validate(&trades, &ids)?;
+    // 読み込めたトレードだけを書き換える
+    let ids: Vec<i64> = trades.iter().filter_map(|t| t.id).map(|id| id as i64).collect();
 
-    let new_trade = merge(&trades);
-    dbg!(&new_trade);
+    let time_policy = match time_policy {
+        Some(p) => p,
+        None => settings::load::<MergeConfig>(db, MERGE_CONFIG_KEY)?.time_policy,
+    };
+    let new_trade = merge(&trades, time_policy);
 
-    let merge_to_result = trades::insert_trade(db, new_trade);
-    let merge_to = match merge_to_result {
-        Ok(r) => r,
+    // 途中で失敗したら元のトレードを残したままにする
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+
+    let merge_to = match trades::insert_trade_tx(&tx, new_trade) {
+        Ok((id, _)) => id,
         Err(e) => {
-            eprintln!("❌ Failed to load trades: {}", e);
+            eprintln!("❌ Failed to insert merged trade: {}", e);
             return Err(e);
         }
     };
 
-    trades::delete_by_ids(db, ids.clone())?;
-    trades::update_merge_to(db, ids.clone(), merge_to)?;
+    // 元のトレードのラベルはすべて引き継ぐ
+    for id in &ids {
+        let label_ids = trade_label::find_by_trade_id_tx(&tx, *id as i32)?;
+        copy_labels(&tx, &label_ids, merge_to as i32)?;
+    }
+
+    trades::delete_by_ids_tx(&tx, &ids)?;
+    trades::update_merge_to_tx(&tx, &ids, merge_to)?;
+
+    tx.commit().map_err(|e| e.to_string())?;
+    return Ok(merge_to);
+}
+
+/// マージを取り消す。元のトレードを戻し、マージ後のトレードは削除する
+/// マージ後に付けたラベルとメモは元のトレードすべてに引き継ぐ
+pub fn unmerge_trade(db: &DbState, id: i64) -> Result<Vec<Trade>, String> {
+    let merged = trades::find_by_id(db, id)?.ok_or("トレードが見つかりません")?;
+    let children = trades::find_by_merged_to(db, id)?;
+    if children.is_empty() {
+        return Err("マージされたトレードではありません".into());
+    }
+
+    let child_ids: Vec<i64> = children.iter().filter_map(|t| t.id).map(|id| id as i64).collect();
+
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    trades::restore_by_ids_tx(&tx, &child_ids)?;
+
+    // マージ後に付け足したラベル・メモだけを元のトレードに戻す
+    let mut child_labels = Vec::new();
+    for child_id in &child_ids {
+        child_labels.extend(trade_label::find_by_trade_id_tx(&tx, *child_id as i32)?);
+    }
+    let added_labels: Vec<i32> = trade_label::find_by_trade_id_tx(&tx, id as i32)?
+        .into_iter()
+        .filter(|l| !child_labels.contains(l))
+        .collect();
+    let added_memo: Vec<&str> = merged
+        .memo
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .filter(|line| !children.iter().any(|c| c.memo.lines().any(|l| l == *line)))
+        .collect();
+
+    for child in &children {
+        copy_labels(&tx, &added_labels, child.id.unwrap_or(0))?;
+
+        if !added_memo.is_empty() {
+            let mut lines: Vec<&str> = child.memo.lines().collect();
+            lines.extend(&added_memo);
+            trades::update_trade_memo_by_id_tx(
+                &tx,
+                Trade {
+                    id: child.id,
+                    memo: lines.join("\n").trim().to_string(),
+                    ..Default::default()
+                },
+            )?;
+        }
+    }
+
+    trades::purge_by_id_tx(&tx, id)?;
+    tx.commit().map_err(|e| e.to_string())?;
+    drop(conn);
 
-    return Ok(());
+    trades::get_by_ids(db, child_ids)
 }
 
-pub fn validate(trades: &Vec<Trade>) -> Result<(), String> {
-    if trades.len() < 2 {
+/// トレードをロットごとに分割する。損益・スワップはロットで按分し、端数は最後に寄せる
+/// 元のトレードは削除扱いにし、ラベルとメモは分割後のすべてに付ける
+pub fn split_trade(db: &DbState, id: i64, lots: Vec<f64>) -> Result<Vec<Trade>, String> {
+    let trade = trades::find_by_id(db, id)?.ok_or("トレードが見つかりません")?;
+
+    if lots.len() < 2 {
+        return Err("分割には2つ以上のロットが必要です".into());
+    }
+    if lots.iter().any(|lot| *lot <= 0.0) {
+        return Err("ロットは0より大きい値を指定してください".into());
+    }
+    let total: f64 = lots.iter().sum();
+    if (total - trade.lot).abs() > 1e-6 {
+        return Err(format!(
+            "ロットの合計 ({}) が元のトレード ({}) と一致しません",
+            total, trade.lot
+        ));
+    }
+
+    let label_ids = trade_label::find_by_trade_id(db, id as i32)?;
+    let swap = trade.swap.unwrap_or(0);
+    let mut rest_profit = trade.profit;
+    let mut rest_swap = swap;
+    let mut piece_ids = Vec::new();
+
+    let mut conn = db.conn.lock().map_err(|e| e.to_string())?;
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    for (i, lot) in lots.iter().enumerate() {
+        let is_last = i == lots.len() - 1;
+        let (profit, piece_swap) = if is_last {
+            (rest_profit, rest_swap)
+        } else {
+            let ratio = lot / trade.lot;
+            (
+                (trade.profit as f64 * ratio).round() as i32,
+                (swap as f64 * ratio).round() as i32,
+            )
+        };
+        rest_profit -= profit;
+        rest_swap -= piece_swap;
+
+        let piece = Trade {
+            id: None,
+            lot: *lot,
+            profit,
+            swap: trade.swap.map(|_| piece_swap),
+            ..trade.clone()
+        };
+        let piece_id = trades::insert_split_trade_tx(&tx, piece, id, i as i64 + 1)?;
+        copy_labels(&tx, &label_ids, piece_id as i32)?;
+        piece_ids.push(piece_id);
+    }
+
+    trades::delete_by_ids_tx(&tx, &[id])?;
+    tx.commit().map_err(|e| e.to_string())?;
+    drop(conn);
+
+    trades::get_by_ids(db, piece_ids)
+}
+
+/// まだ付いていないラベルだけ付ける
+fn copy_labels(tx: &Transaction, label_ids: &[i32], trade_id: i32) -> Result<(), String> {
+    let existing = trade_label::find_by_trade_id_tx(tx, trade_id)?;
+    for label_id in label_ids {
+        if !existing.contains(label_id) {
+            trade_label::insert_trade_label_tx(tx, trade_id, *label_id)?;
+        }
+    }
+    Ok(())
+}
+
+/// 削除済み・マージ済みのトレードは読み込まれないので、件数が合わなければマージしない
+pub fn validate(trades: &Vec<Trade>, ids: &[i64]) -> Result<(), String> {
+    if ids.len() < 2 {
         return Err("マージには2件以上のトレードが必要です".into());
     }
+    if trades.len() != ids.len() {
+        return Err("削除済みまたはマージ済みのトレードはマージできません".into());
+    }
+
+    if trades.iter().any(|t| t.lot <= 0.0) {
+        return Err("ロットが0のトレードはマージできません".into());
+    }
 
     let first_pair = &trades[0].pair;
     if trades.iter().any(|t| t.pair != *first_pair) {
@@ -70,33 +407,65 @@ pub fn validate(trades: &Vec<Trade>) -> Result<(), String> {
     Ok(())
 }
 
-fn merge(trades: &Vec<Trade>) -> Trade {
+/// ロットで加重平均してまとめる。レートは丸めずに保持する
+fn merge(trades: &Vec<Trade>, time_policy: MergeTimePolicy) -> Trade {
     let total_lot: f64 = trades.iter().map(|t| t.lot).sum();
-    let entry_rate_avg =
-        (trades.iter().map(|t| t.entry_rate).sum::<f64>() / trades.len() as f64 * 1000.0).round()
-            / 1000.0;
-    let exit_rate_avg =
-        (trades.iter().map(|t| t.exit_rate).sum::<f64>() / trades.len() as f64 * 1000.0).round()
-            / 1000.0;
-    let entry_time_avg = trades.iter().map(|t| t.entry_time).sum::<i64>() / trades.len() as i64;
-    let exit_time_avg = trades.iter().map(|t| t.exit_time).sum::<i64>() / trades.len() as i64;
+    let weighted = |f: fn(&Trade) -> f64| {
+        trades.iter().map(|t| f(t) * t.lot).sum::<f64>() / total_lot
+    };
+
+    let entry_rate = weighted(|t| t.entry_rate);
+    let exit_rate = weighted(|t| t.exit_rate);
+
+    let (entry_time, exit_time) = match time_policy {
+        MergeTimePolicy::Weighted => (
+            weighted(|t| t.entry_time as f64).round() as i64,
+            weighted(|t| t.exit_time as f64).round() as i64,
+        ),
+        MergeTimePolicy::Span => (
+            trades.iter().map(|t| t.entry_time).min().unwrap_or(0),
+            trades.iter().map(|t| t.exit_time).max().unwrap_or(0),
+        ),
+    };
+
+    // pips は通貨ペアごとの単位で計算済みなので、レートから計算し直さずに加重平均する
+    let profit_pips = weighted(|t| t.profit_pips as f64).round() as i32;
 
     let total_profit = trades.iter().map(|t| t.profit).sum();
-    let direction = if trades[0].side == "買" { 1.0 } else { -1.0 };
-    let total_pips = ((exit_rate_avg - entry_rate_avg) * 1000.0 * direction).round() as i32;
-    let total_swap = trades.iter().map(|t| t.swap).sum();
+    let total_swap = trades
+        .iter()
+        .filter_map(|t| t.swap)
+        .reduce(|a, b| a + b);
+
+    // 同じメモは 1 回だけ残す
+    let mut memos: Vec<&str> = Vec::new();
+    for t in trades {
+        let memo = t.memo.trim();
+        if !memo.is_empty() && !memos.contains(&memo) {
+            memos.push(memo);
+        }
+    }
+
+    let account = &trades[0].account;
+    let account = if trades.iter().all(|t| t.account == *account) {
+        account.clone()
+    } else {
+        String::new()
+    };
 
     return Trade {
         pair: trades[0].pair.clone(),
         side: trades[0].side.clone(),
-        lot: total_lot,
-        entry_rate: entry_rate_avg,
-        exit_rate: exit_rate_avg,
-        entry_time: entry_time_avg,
-        exit_time: exit_time_avg,
+        lot: (total_lot * 1e8).round() / 1e8,
+        entry_rate,
+        exit_rate,
+        entry_time,
+        exit_time,
         profit: total_profit,
-        profit_pips: total_pips,
+        profit_pips,
         swap: total_swap,
+        memo: memos.join("\n"),
+        account,
         ..Default::default()
     };
 }
@@ -0,0 +1,41 @@
+/// 境界から [min, max) の区間を作る。最初は下限なし、最後は上限なし
+/// 0 以下の境界は無視する
+pub fn ranges<T>(bounds: &[T]) -> Vec<(Option<T>, Option<T>)>
+where
+    T: Copy + PartialOrd + Default,
+{
+    let mut bounds: Vec<T> = bounds
+        .iter()
+        .copied()
+        .filter(|b| *b > T::default())
+        .collect();
+    bounds.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    bounds.dedup();
+
+    let mut buckets = Vec::new();
+    let mut min = None;
+    for b in bounds {
+        buckets.push((min, Some(b)));
+        min = Some(b);
+    }
+    buckets.push((min, None));
+    buckets
+}
+
+/// 値が区間 [min, max) に入るか
+pub fn contains<T>(min: Option<T>, max: Option<T>, value: T) -> bool
+where
+    T: Copy + PartialOrd,
+{
+    min.is_none_or(|min| value >= min) && max.is_none_or(|max| value < max)
+}
+
+/// "<5m", "5m-1h", "1d+" のような区間の表示名
+pub fn name<T>(min: Option<T>, max: Option<T>, format: impl Fn(T) -> String) -> String {
+    match (min, max) {
+        (None, Some(max)) => format!("<{}", format(max)),
+        (Some(min), Some(max)) => format!("{}-{}", format(min), format(max)),
+        (Some(min), None) => format!("{}+", format(min)),
+        (None, None) => "all".to_string(),
+    }
+}
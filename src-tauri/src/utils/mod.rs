@@ -1 +1,2 @@
 pub mod time_utils;
+pub mod buckets;
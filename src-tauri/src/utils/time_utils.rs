@@ -50,3 +50,30 @@ pub fn get_unix_range_from_business_date(date_str: &str) -> Option<(i64, i64)> {
 
     Some((start_jst_timestamp, end_jst_timestamp))
 }
+
+/// UNIX time → "YYYY/MM/DD hh:mm"（JST）
+pub fn unix_to_jst_str(unix: i64) -> String {
+    match Tokyo.timestamp_opt(unix, 0).single() {
+        Some(dt) => dt.format("%Y/%m/%d %H:%M").to_string(),
+        None => String::new(),
+    }
+}
+
+/// UNIX time → JST の時（0-23）
+pub fn jst_hour(unix: i64) -> u32 {
+    use chrono::Timelike;
+    Tokyo
+        .timestamp_opt(unix, 0)
+        .single()
+        .map(|dt| dt.hour())
+        .unwrap_or(0)
+}
+
+/// UNIX time → JST の曜日（月曜 0 - 日曜 6）
+pub fn jst_weekday(unix: i64) -> u32 {
+    Tokyo
+        .timestamp_opt(unix, 0)
+        .single()
+        .map(|dt| dt.weekday().num_days_from_monday())
+        .unwrap_or(0)
+}
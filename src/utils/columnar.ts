@@ -0,0 +1,50 @@
+// stream_candles / stream_ticks から届くバイナリチャンクのデコード
+// ヘッダー: [kind: u8][is_last: u8][reserved: u16][count: u32] (LE)
+// 以降は列ごとの Float64Array
+
+const HEADER_SIZE = 8
+
+export interface CandleChunk {
+  isLast: boolean
+  time: Float64Array
+  open: Float64Array
+  high: Float64Array
+  low: Float64Array
+  close: Float64Array
+}
+
+export interface TickChunk {
+  isLast: boolean
+  timeMsc: Float64Array
+  bid: Float64Array
+  ask: Float64Array
+}
+
+const toAlignedBuffer = (data: ArrayBuffer | Uint8Array): ArrayBuffer => {
+  if (data instanceof ArrayBuffer) return data
+  // Float64Array は 8byte 境界が必要なのでコピーして揃える
+  return data.slice().buffer as ArrayBuffer
+}
+
+const readColumns = (buffer: ArrayBuffer, columns: number) => {
+  const view = new DataView(buffer)
+  const isLast = view.getUint8(1) === 1
+  const count = view.getUint32(4, true)
+  const result: Float64Array[] = []
+  for (let i = 0; i < columns; i++) {
+    result.push(new Float64Array(buffer, HEADER_SIZE + i * count * 8, count))
+  }
+  return { isLast, result }
+}
+
+export const decodeCandleChunk = (data: ArrayBuffer | Uint8Array): CandleChunk => {
+  const { isLast, result } = readColumns(toAlignedBuffer(data), 5)
+  const [time, open, high, low, close] = result
+  return { isLast, time, open, high, low, close }
+}
+
+export const decodeTickChunk = (data: ArrayBuffer | Uint8Array): TickChunk => {
+  const { isLast, result } = readColumns(toAlignedBuffer(data), 3)
+  const [timeMsc, bid, ask] = result
+  return { isLast, timeMsc, bid, ask }
+}